@@ -1,169 +1,575 @@
-use anyhow::Result;
-use futures::StreamExt;
-use parity_scale_codec::Decode;
-use sp_keyring::{sr25519::sr25519::Pair, AccountKeyring};
+use anyhow::{anyhow, bail, ensure, Result};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
 use subxt::{
-    extrinsic::{BaseExtrinsicParams, PlainTip},
+    sp_core::{crypto::Ss58Codec, H256},
     sp_runtime::AccountId32,
-    BasicError, ClientBuilder, DefaultConfig, EventDetails, PairSigner, PolkadotExtrinsicParams,
 };
 
-use crate::runtime::develop_v105::api::{
-    balances::calls::TransactionApi as BalancesTransactionApi,
-    runtime_types::{
-        appchain_deip_runtime::Call,
-        frame_system::pallet::{Error as SystemError, Event as SystemEvent},
-        pallet_sudo::pallet::Event as SudoEvent,
-        sp_runtime::DispatchError,
+use crate::{
+    app::{key_matches, DeipRuntime, MapKeys},
+    cli::{self, parse_h160, DeipFNFTCall, DeipFNFTStorage},
+    migration::{AssetState, MetadataState, PreMigration},
+    output,
+    runtime::develop_v105::api::{
+        runtime_types::{
+            appchain_deip_runtime::Call,
+            deip_asset_system::nft_impl::{
+                NFTokenCollectionRecord, NFTokenFractionRecord, NFTokenItemRecord,
+            },
+            pallet_deip_f_nft::pallet::Event as FNFTEvent,
+            primitive_types::H160,
+            sp_runtime::DispatchError,
+        },
+        Event,
     },
-    Event, RuntimeApi, PALLETS,
 };
 
-pub struct App {
-    pub client: RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>,
-}
+deip_glue!(runtime::develop_v105::api, DeipFNFT => describe_fnft_event);
+
+impl DeipRuntime for App {
+    const NAME: &'static str = "develop";
+    const SPEC_VERSION: u32 = 105;
+
+    type Event = Event;
+    type DispatchError = DispatchError;
+    type Call = Call;
+
+    deip_methods!();
 
-impl App {
-    pub async fn build() -> Result<Self> {
-        let client = ClientBuilder::new()
-            .build()
-            .await?
-            .to_runtime_api::<RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>>();
-        Ok(Self { client })
+    async fn deip_fnft_tx(&self, call: DeipFNFTCall) -> Result<()> {
+        let fnft = self.client.tx().deip_fnft();
+        match call {
+            DeipFNFTCall::CreateCollection {
+                signer,
+                collection,
+                max_items,
+            } => {
+                let tx = fnft.create_collection(H160(collection.0), max_items)?;
+                self.submit(tx, signer).await
+            }
+            DeipFNFTCall::MintItem {
+                signer,
+                collection,
+                item,
+            } => {
+                let tx = fnft.mint_item(H160(collection.0), item)?;
+                self.submit(tx, signer).await
+            }
+            DeipFNFTCall::TransferItem { signer, item, to } => {
+                self.submit(fnft.transfer_item(item, to.into())?, signer)
+                    .await
+            }
+            DeipFNFTCall::MintFraction {
+                signer,
+                item,
+                amount,
+            } => self.submit(fnft.mint_fraction(item, amount)?, signer).await,
+            DeipFNFTCall::BurnFraction {
+                signer,
+                item,
+                amount,
+            } => self.submit(fnft.burn_fraction(item, amount)?, signer).await,
+            DeipFNFTCall::TransferFraction {
+                signer,
+                item,
+                to,
+                amount,
+            } => {
+                let tx = fnft.transfer_fraction(item, to.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+            DeipFNFTCall::FractionalizeItem {
+                signer,
+                item,
+                total_amount,
+                limited,
+            } => {
+                let tx = fnft.fractionalize_item(item, total_amount, limited)?;
+                self.submit(tx, signer).await
+            }
+        }
     }
 
-    pub async fn _spawn_events_listener(&self) {
-        tokio::spawn(async {
-            let client = ClientBuilder::new()
-                .build()
-                .await
-                .unwrap()
-                .to_runtime_api::<RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>>();
-            let mut events_sub = client.events().subscribe().await.unwrap();
-            while let Some(events) = events_sub.next().await {
-                let events = events.unwrap();
-                let hash = events.block_hash();
-                info!("Events in block {hash:?}\n");
-                for event in events.iter() {
-                    let event = event.unwrap();
-                    let event = event.event;
-                    info!("    {event:?}\n");
+    async fn deip_fnft_storage(&self, storage: DeipFNFTStorage) -> Result<()> {
+        let fnft = self.client.storage().deip_fnft();
+        match storage {
+            DeipFNFTStorage::CollectionRepo {
+                collection: Some(collection),
+            } => match fnft.collection_repo(&H160(collection.0), None).await? {
+                Some(record) => display_collection(&record)?,
+                None => bail!("Collection {collection:?} not found"),
+            },
+            DeipFNFTStorage::CollectionRepo { collection: None } => {
+                let mut key_iter = fnft.collection_repo_iter(None).await?;
+                while let Some((_, record)) = key_iter.next().await? {
+                    display_collection(&record)?;
+                }
+            }
+            DeipFNFTStorage::ItemRepo {
+                fingerprint: Some(fingerprint),
+            } => match fnft.item_repo(&fingerprint, None).await? {
+                Some(record) => display_item(&record)?,
+                None => bail!("Item {fingerprint:?} not found"),
+            },
+            DeipFNFTStorage::ItemRepo { fingerprint: None } => {
+                let mut key_iter = fnft.item_repo_iter(None).await?;
+                while let Some((_, record)) = key_iter.next().await? {
+                    display_item(&record)?;
+                }
+            }
+            DeipFNFTStorage::FractionRepo {
+                fingerprint: Some(fingerprint),
+                account: Some(account),
+            } => match fnft.fraction_repo(&fingerprint, &account.0, None).await? {
+                Some(record) => display_fraction(&record)?,
+                None => bail!("No fraction of {fingerprint:?} held by {}", account.0),
+            },
+            DeipFNFTStorage::FractionRepo {
+                fingerprint,
+                account,
+            } => {
+                let account = account.map(|account| account.0);
+                let mut key_iter = fnft.fraction_repo_iter(None).await?;
+                while let Some((_, record)) = key_iter.next().await? {
+                    if key_matches(&fingerprint, &record.fingerprint)
+                        && key_matches(&account, &record.account)
+                    {
+                        display_fraction(&record)?;
+                    }
+                }
+            }
+            DeipFNFTStorage::FractionalRepo {
+                fingerprint: Some(fingerprint),
+            } => match fnft.fractional_repo(&fingerprint, None).await? {
+                Some((id, total)) => display_fractional(fingerprint, id, total)?,
+                None => bail!("Item {fingerprint:?} is not fractionalized"),
+            },
+            DeipFNFTStorage::FractionalRepo { fingerprint: None } => {
+                let keys = MapKeys::new(self.client(), "DeipFNFT", "FractionalRepo")?;
+                let mut key_iter = fnft.fractional_repo_iter(None).await?;
+                while let Some((storage_key, (id, total))) = key_iter.next().await? {
+                    let fingerprint: H256 = keys.read(&storage_key.0).next_key()?;
+                    display_fractional(fingerprint, id, total)?;
                 }
             }
-        });
+            DeipFNFTStorage::FractionHolds {
+                fingerprint: Some(fingerprint),
+                account: Some(account),
+                hold_id: Some(hold_id),
+                hold_index: Some(hold_index),
+            } => {
+                let hold = fnft
+                    .fraction_holds(
+                        &fingerprint,
+                        &account.0,
+                        &H160(hold_id.0),
+                        &hold_index,
+                        None,
+                    )
+                    .await?;
+                match hold {
+                    Some((id, amount)) => display_hold(
+                        fingerprint,
+                        &account.0,
+                        hold_id,
+                        hold_index,
+                        glue::h160(&id),
+                        amount,
+                    )?,
+                    None => bail!("Hold {hold_id:?}/{hold_index} not found"),
+                }
+            }
+            DeipFNFTStorage::FractionHolds {
+                fingerprint,
+                account,
+                hold_id,
+                hold_index,
+            } => {
+                let account = account.map(|account| account.0);
+                let keys = MapKeys::new(self.client(), "DeipFNFT", "FractionHolds")?;
+                let mut key_iter = fnft.fraction_holds_iter(None).await?;
+                while let Some((storage_key, (id, amount))) = key_iter.next().await? {
+                    let mut key = keys.read(&storage_key.0);
+                    let key_fingerprint: H256 = key.next_key()?;
+                    let key_account: AccountId32 = key.next_key()?;
+                    let key_hold_id: H160 = key.next_key()?;
+                    let key_hold_index: u32 = key.next_key()?;
+                    if key_matches(&fingerprint, &key_fingerprint)
+                        && key_matches(&account, &key_account)
+                        && key_matches(&hold_id, &glue::h160(&key_hold_id))
+                        && key_matches(&hold_index, &key_hold_index)
+                    {
+                        display_hold(
+                            key_fingerprint,
+                            &key_account,
+                            glue::h160(&key_hold_id),
+                            key_hold_index,
+                            glue::h160(&id),
+                            amount,
+                        )?;
+                    }
+                }
+            }
+            DeipFNFTStorage::NextCollectionId { check } => {
+                let id = fnft.next_collection_id(None).await?;
+                display_value("DeipFNFT::NextCollectionId", id)?;
+                expect_value("DeipFNFT::NextCollectionId", id, check.expect)?;
+            }
+            DeipFNFTStorage::NextFTokenId { check } => {
+                let id = fnft.next_f_token_id(None).await?;
+                display_value("DeipFNFT::NextFTokenId", id)?;
+                expect_value("DeipFNFT::NextFTokenId", id, check.expect)?;
+            }
+            DeipFNFTStorage::FingerprintByFractionTokenId { id: Some(id) } => {
+                match fnft.fingerprint_by_fraction_token_id(&id, None).await? {
+                    Some(fingerprint) => display_ftoken(id, fingerprint)?,
+                    None => bail!("FToken {id} not found"),
+                }
+            }
+            DeipFNFTStorage::FingerprintByFractionTokenId { id: None } => {
+                let keys = MapKeys::new(self.client(), "DeipFNFT", "FingerprintByFractionTokenId")?;
+                let mut key_iter = fnft.fingerprint_by_fraction_token_id_iter(None).await?;
+                while let Some((storage_key, fingerprint)) = key_iter.next().await? {
+                    let id: u32 = keys.read(&storage_key.0).next_key()?;
+                    display_ftoken(id, fingerprint)?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    pub fn tx_balances(
-        &self,
-    ) -> BalancesTransactionApi<DefaultConfig, BaseExtrinsicParams<DefaultConfig, PlainTip>> {
-        self.client.tx().balances()
-    }
+    async fn verify_migration(&self, pre: &PreMigration) -> Result<Vec<String>> {
+        let storage = self.client.storage();
+        let mut mismatches = Vec::new();
 
-    fn signer(&self, account: AccountKeyring) -> PairSigner<DefaultConfig, Pair> {
-        PairSigner::new(account.pair())
-    }
+        for (id, expected) in &pre.assets {
+            match storage.assets().asset(id, None).await? {
+                Some(details) => {
+                    let found = AssetState {
+                        owner: details.owner.to_ss58check(),
+                        supply: details.supply,
+                    };
+                    if &found != expected {
+                        mismatches.push(format!(
+                            "Assets::Asset({id}): expected {expected:?}, found {found:?}"
+                        ));
+                    }
+                }
+                None => mismatches.push(format!("Assets::Asset({id}): missing")),
+            }
+        }
+        for (deip_id, id) in &pre.deip_assets {
+            if storage.assets().asset(id, None).await?.is_none() {
+                mismatches.push(format!(
+                    "DeipAssets({deip_id}): Assets::Asset({id}) missing"
+                ));
+            }
+        }
 
-    pub async fn sudo_unchecked_weight(&self, account: AccountKeyring, call: Call) {
-        let signer = self.signer(account);
-        self.client
-            .tx()
-            .sudo()
-            .sudo_unchecked_weight(call, 0)
-            .unwrap()
-            .sign_and_submit_then_watch_default(&signer)
-            .await
-            .unwrap()
-            .wait_for_in_block()
-            .await
-            .unwrap()
-            .fetch_events()
-            .await
-            .unwrap()
-            .iter()
-            .map(|res| res.unwrap())
-            .for_each(display_event);
-
-        info!("Sudo: runtime upgrade finished\n");
-    }
+        let next_ftoken_id = storage.deip_fnft().next_f_token_id(None).await?;
+        if next_ftoken_id != pre.next_ftoken_id() {
+            mismatches.push(format!(
+                "DeipFNFT::NextFTokenId: expected {}, found {next_ftoken_id}",
+                pre.next_ftoken_id()
+            ));
+        }
 
-    pub async fn free_core_token_balance(&self, account: &AccountId32) -> Result<u128, BasicError> {
-        self.client
-            .storage()
-            .system()
-            .account(account, None)
-            .await
-            .map(|info| info.data.free)
-    }
-}
+        for (deip_id, class_id) in &pre.nft_classes {
+            let collection_id = parse_h160(deip_id).map_err(|err| anyhow!("{deip_id}: {err}"))?;
+            let collection_id = H160(collection_id.0);
+            match storage
+                .deip_fnft()
+                .collection_repo(&collection_id, None)
+                .await?
+            {
+                Some(record)
+                    if record.collection_id.0 == collection_id.0
+                        && record.internal_id == *class_id => {}
+                Some(record) => mismatches.push(format!(
+                    "DeipFNFT::CollectionRepo({deip_id}): expected internal_id {class_id}, \
+                     found {record:?}"
+                )),
+                None => mismatches.push(format!("DeipFNFT::CollectionRepo({deip_id}): missing")),
+            }
+        }
 
-pub fn display_event(event: EventDetails<Event>) {
-    let event = event.event;
-    match event {
-        Event::System(event) => match event {
-            SystemEvent::ExtrinsicSuccess(info) => info!(" - Success::{info:?}"),
-            SystemEvent::ExtrinsicFailed(error, _) => match error {
-                DispatchError::Other => todo!(),
-                DispatchError::CannotLookup => todo!(),
-                DispatchError::BadOrigin => todo!(),
-                DispatchError::Module { index, error } => decode_module_error(index, error),
-                DispatchError::ConsumerRemaining => todo!(),
-                DispatchError::NoProviders => todo!(),
-                DispatchError::Token(error) => info!(" - System::Failed::Token::{error:?}"),
-                DispatchError::Arithmetic(_) => todo!(),
-            },
-            SystemEvent::CodeUpdated => info!(" - CodeUpdated"),
-            SystemEvent::KilledAccount(_) => todo!(),
-            SystemEvent::Remarked(_, _) => todo!(),
-            system_event => info!(" - {system_event:?}"),
-        },
-        Event::Balances(event) => info!(" - Balances::{event:?}"),
-        Event::OctopusAppchain(_) => todo!(),
-        Event::OctopusLpos(_) => todo!(),
-        Event::OctopusUpwardMessages(_) => todo!(),
-        Event::Session(_) => todo!(),
-        Event::Grandpa(_) => todo!(),
-        Event::Sudo(event) => match event {
-            SudoEvent::Sudid { sudo_result } => {
-                if let Err(err) = sudo_result {
-                    match err {
-                        DispatchError::Other => todo!(),
-                        DispatchError::CannotLookup => todo!(),
-                        DispatchError::BadOrigin => todo!(),
-                        DispatchError::Module { index, error } => decode_module_error(index, error),
-                        DispatchError::ConsumerRemaining => todo!(),
-                        DispatchError::NoProviders => todo!(),
-                        DispatchError::Token(_) => todo!(),
-                        DispatchError::Arithmetic(_) => todo!(),
+        // Fraction tokens and collection owners of the DeipFNFT items.
+        let mut items_by_ftoken = BTreeMap::new();
+        let mut owners_by_collection: BTreeMap<u32, BTreeSet<AccountId32>> = BTreeMap::new();
+        let mut key_iter = storage.deip_fnft().item_repo_iter(None).await?;
+        while let Some((_, item)) = key_iter.next().await? {
+            if let Some((ftoken, _)) = item.fractional {
+                items_by_ftoken.insert(ftoken, item.fingerprint);
+            }
+            owners_by_collection
+                .entry(item.collection_id)
+                .or_default()
+                .insert(item.account);
+        }
+        let mut holds = BTreeMap::<_, u32>::new();
+        let keys = MapKeys::new(self.client(), "DeipFNFT", "FractionHolds")?;
+        let mut key_iter = storage.deip_fnft().fraction_holds_iter(None).await?;
+        while let Some((storage_key, _)) = key_iter.next().await? {
+            let mut key = keys.read(&storage_key.0);
+            let fingerprint: H256 = key.next_key()?;
+            let account: AccountId32 = key.next_key()?;
+            *holds.entry((fingerprint, account)).or_default() += 1;
+        }
+        let ftoken_item = |deip_id: &String| {
+            let id = pre
+                .deip_assets
+                .get(deip_id)
+                .ok_or_else(|| format!("DeipAssets({deip_id}): not in AssetIdByDeipAssetId"))?;
+            let fingerprint = items_by_ftoken.get(id).ok_or_else(|| {
+                format!(
+                    "DeipAssets({deip_id}): no DeipFNFT::ItemRepo item fractionalized into {id}"
+                )
+            })?;
+            Ok::<_, String>((*id, *fingerprint))
+        };
+
+        for (deip_id, balances) in &pre.ft_balances {
+            let fingerprint = match ftoken_item(deip_id) {
+                Ok((_, fingerprint)) => fingerprint,
+                Err(mismatch) => {
+                    mismatches.push(mismatch);
+                    continue;
+                }
+            };
+            for (holder, amount) in balances {
+                let account = AccountId32::from_ss58check(holder)
+                    .map_err(|err| anyhow!("{holder}: {err:?}"))?;
+                let entry = format!("DeipFNFT::FractionRepo({fingerprint:?}, {holder})");
+                match storage
+                    .deip_fnft()
+                    .fraction_repo(&fingerprint, &account, None)
+                    .await?
+                {
+                    Some(fraction) if fraction.amount != *amount => mismatches.push(format!(
+                        "{entry}: expected amount {amount}, found {}",
+                        fraction.amount
+                    )),
+                    Some(fraction) => {
+                        let found = holds.get(&(fingerprint, account)).copied().unwrap_or(0);
+                        if fraction.holds != found {
+                            mismatches.push(format!(
+                                "{entry}: {} holds, {found} FractionHolds entries",
+                                fraction.holds
+                            ));
+                        }
                     }
+                    None => mismatches.push(format!("{entry}: missing, expected amount {amount}")),
                 }
             }
+        }
 
-            SudoEvent::KeyChanged { .. } => todo!(),
-            SudoEvent::SudoAsDone { .. } => todo!(),
-        },
-        Event::ImOnline(_) => todo!(),
-        Event::Assets(event) => info!(" - Assets::{event:?}"),
-        Event::Uniques(event) => info!(" - Uniques::{event:?}"),
-        Event::Multisig(_) => todo!(),
-        Event::Utility(_) => todo!(),
-        Event::Deip(_) => todo!(),
-        Event::DeipProposal(_) => todo!(),
-        Event::DeipDao(_) => todo!(),
-        Event::DeipVesting(_) => todo!(),
-        Event::DeipInvestmentOpportunity(_) => todo!(),
-        Event::DeipFNFT(_) => todo!(),
+        for (deip_id, expected) in &pre.asset_metadata {
+            let id = match ftoken_item(deip_id) {
+                Ok((id, _)) => id,
+                Err(mismatch) => {
+                    mismatches.push(mismatch);
+                    continue;
+                }
+            };
+            let metadata = storage.assets().metadata(&id, None).await?;
+            let found = MetadataState::new(&metadata.name.0, &metadata.symbol.0, metadata.decimals);
+            if &found != expected {
+                mismatches.push(format!(
+                    "Assets::Metadata({id}): expected {expected:?}, found {found:?}"
+                ));
+            }
+        }
+
+        for (deip_id, holders) in &pre.nft_balances {
+            let class_id = match pre.nft_classes.get(deip_id) {
+                Some(class_id) => class_id,
+                None => {
+                    mismatches.push(format!(
+                        "DeipUniques({deip_id}): not in NftClassIdByDeipNftClassId"
+                    ));
+                    continue;
+                }
+            };
+            let owners = owners_by_collection.get(class_id);
+            for holder in holders {
+                let account = AccountId32::from_ss58check(holder)
+                    .map_err(|err| anyhow!("{holder}: {err:?}"))?;
+                if !owners.is_some_and(|owners| owners.contains(&account)) {
+                    mismatches.push(format!(
+                        "DeipFNFT::ItemRepo: {holder} owns no item of collection {deip_id}"
+                    ));
+                }
+            }
+        }
+
+        let next_collection_id = storage.deip_fnft().next_collection_id(None).await?;
+        if next_collection_id < pre.next_nft_class_id {
+            mismatches.push(format!(
+                "DeipFNFT::NextCollectionId: {next_collection_id} is below \
+                 DeipUniques::NextNftClassId {}",
+                pre.next_nft_class_id
+            ));
+        }
+
+        Ok(mismatches)
+    }
+}
+
+type CollectionRecord = NFTokenCollectionRecord<AccountId32, H160, u32, u32>;
+type ItemRecord = NFTokenItemRecord<AccountId32, H256, u32, u32, (u32, u128)>;
+type FractionRecord = NFTokenFractionRecord<AccountId32, H256, (u32, u128), u128, u32>;
+
+fn display_collection(record: &CollectionRecord) -> Result<()> {
+    let collection = json!({
+        "collection_id": glue::h160(&record.collection_id),
+        "internal_id": record.internal_id,
+        "account": record.account,
+        "items": record.items,
+        "max_items": record.max_items,
+    });
+    output::emit(&collection, |_| {
+        info!(
+            "Collection {:?}: internal id {}, owner {}, {}/{} items",
+            glue::h160(&record.collection_id),
+            record.internal_id,
+            record.account,
+            record.items,
+            record.max_items,
+        )
+    })
+}
+
+fn display_item(record: &ItemRecord) -> Result<()> {
+    let item = json!({
+        "fingerprint": record.fingerprint,
+        "collection_id": record.collection_id,
+        "item_id": record.item_id,
+        "account": record.account,
+        "fractional": record.fractional,
+    });
+    output::emit(&item, |_| {
+        let fractional = match record.fractional {
+            Some((id, total)) => format!("ftoken {id}, total {total}"),
+            None => "not fractionalized".to_owned(),
+        };
+        info!(
+            "Item {:?}: collection {}, item {}, owner {}, {fractional}",
+            record.fingerprint, record.collection_id, record.item_id, record.account,
+        )
+    })
+}
+
+fn display_fraction(record: &FractionRecord) -> Result<()> {
+    let (id, total) = record.fractional;
+    let fraction = json!({
+        "fingerprint": record.fingerprint,
+        "account": record.account,
+        "ftoken_id": id,
+        "total": total,
+        "amount": record.amount,
+        "holds": record.holds,
+    });
+    output::emit(&fraction, |_| {
+        info!(
+            "Fraction of {:?} by {}: {}/{total} of ftoken {id}, {} holds",
+            record.fingerprint, record.account, record.amount, record.holds,
+        )
+    })
+}
+
+fn display_fractional(fingerprint: H256, id: u32, total: u128) -> Result<()> {
+    let fractional = json!({ "fingerprint": fingerprint, "ftoken_id": id, "total": total });
+    output::emit(&fractional, |_| {
+        info!("Fractional {fingerprint:?}: ftoken {id}, total {total}")
+    })
+}
+
+fn display_hold(
+    fingerprint: H256,
+    account: &AccountId32,
+    hold_id: subxt::sp_core::H160,
+    hold_index: u32,
+    id: subxt::sp_core::H160,
+    amount: u32,
+) -> Result<()> {
+    let hold = json!({
+        "fingerprint": fingerprint,
+        "account": account,
+        "hold_id": hold_id,
+        "hold_index": hold_index,
+        "value": [id, amount],
+    });
+    output::emit(&hold, |_| {
+        info!("Hold {hold_id:?}/{hold_index} of {fingerprint:?} by {account}: {id:?}, {amount}")
+    })
+}
+
+fn display_value(entry: &str, value: u32) -> Result<()> {
+    output::emit(&json!({ "entry": entry, "value": value }), |_| {
+        info!("{entry}: {value}")
+    })
+}
+
+fn display_ftoken(id: u32, fingerprint: H256) -> Result<()> {
+    output::emit(
+        &json!({ "ftoken_id": id, "fingerprint": fingerprint }),
+        |_| info!("FToken {id}: {fingerprint:?}"),
+    )
+}
+
+fn expect_value(entry: &str, value: u32, expect: Option<u32>) -> Result<()> {
+    if let Some(expect) = expect {
+        ensure!(value == expect, "{entry}: expected {expect}, found {value}");
     }
+    Ok(())
 }
 
-fn decode_module_error(index: u8, error: u8) {
-    match index {
-        0 => {
-            let error = SystemError::decode(&mut [error].as_ref()).unwrap();
-            info!(
-                " - {}::Sudid::Err::Module::{error:?}",
-                PALLETS[index as usize]
-            );
+fn describe_fnft_event(event: FNFTEvent) -> String {
+    match event {
+        FNFTEvent::CollectionCreated {
+            issuer,
+            collection,
+            max_items,
+        } => format!(
+            "DeipFNFT::CollectionCreated: {:?} by {issuer}, max {max_items} items",
+            glue::h160(&collection),
+        ),
+        FNFTEvent::ItemMinted {
+            collection,
+            item,
+            owner,
+        } => format!(
+            "DeipFNFT::ItemMinted: {item:?} in {:?}, owner {owner}",
+            glue::h160(&collection),
+        ),
+        FNFTEvent::ItemFractionalized {
+            item,
+            issuer,
+            total_amount,
+            limited,
+        } => format!(
+            "DeipFNFT::ItemFractionalized: {item:?} by {issuer} into {total_amount}{}",
+            if limited { ", limited" } else { "" },
+        ),
+        FNFTEvent::ItemTransferred { item, from, to } => {
+            format!("DeipFNFT::ItemTransferred: {item:?} from {from} to {to}")
         }
-        index => panic!(" - Unknown index: {index}"),
+        FNFTEvent::FractionMinted {
+            item,
+            owner,
+            amount,
+        } => format!("DeipFNFT::FractionMinted: {amount} of {item:?} to {owner}"),
+        FNFTEvent::FractionBurned {
+            item,
+            owner,
+            amount,
+        } => format!("DeipFNFT::FractionBurned: {amount} of {item:?} by {owner}"),
+        FNFTEvent::FractionTransferred {
+            item,
+            from,
+            to,
+            amount,
+        } => format!("DeipFNFT::FractionTransferred: {amount} of {item:?} from {from} to {to}"),
     }
 }
@@ -0,0 +1,164 @@
+use anyhow::{anyhow, bail, ensure, Result};
+use parity_scale_codec::{Compact, Decode, Encode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{json, Map, Value};
+use subxt::{sp_runtime::AccountId32, Metadata};
+
+/// Fields of `event`, a runtime event enum, decoded with the type registry of
+/// `metadata`: an object when the fields are named, the value itself for a
+/// single unnamed field. Byte strings are hex encoded, accounts SS58 encoded.
+/// Events that can't be decoded, e.g. holding an unsupported type, fall back
+/// to their hex encoded fields.
+pub fn event_fields(metadata: &Metadata, event: &impl Encode) -> Value {
+    let bytes = event.encode();
+    decode_event(metadata, &bytes).unwrap_or_else(|err| {
+        debug!("Event fields left encoded: {err:#}");
+        let fields = bytes.get(2..).unwrap_or_default();
+        json!(format!("0x{}", hex::encode(fields)))
+    })
+}
+
+fn decode_event(metadata: &Metadata, bytes: &[u8]) -> Result<Value> {
+    let input = &mut &bytes[..];
+    let pallet_index = u8::decode(input)?;
+    let variant_index = u8::decode(input)?;
+    let runtime = metadata.runtime_metadata();
+    let pallet = runtime
+        .pallets
+        .iter()
+        .find(|pallet| pallet.index == pallet_index)
+        .ok_or_else(|| anyhow!("No pallet with index {pallet_index}"))?;
+    let events = pallet
+        .event
+        .as_ref()
+        .ok_or_else(|| anyhow!("{} has no events", pallet.name))?;
+    let variant = match resolve(&runtime.types, events.ty.id())?.type_def() {
+        TypeDef::Variant(def) => def
+            .variants()
+            .iter()
+            .find(|variant| variant.index() == variant_index),
+        _ => None,
+    }
+    .ok_or_else(|| anyhow!("No {} event with index {variant_index}", pallet.name))?;
+    decode_fields(&runtime.types, variant.fields(), input)
+}
+
+fn resolve(types: &PortableRegistry, id: u32) -> Result<&scale_info::Type<PortableForm>> {
+    types
+        .resolve(id)
+        .ok_or_else(|| anyhow!("Type {id} not found in the metadata"))
+}
+
+fn decode_fields(
+    types: &PortableRegistry,
+    fields: &[Field<PortableForm>],
+    input: &mut &[u8],
+) -> Result<Value> {
+    let names: Option<Vec<_>> = fields.iter().map(|field| field.name()).collect();
+    match names {
+        Some(names) if !names.is_empty() => {
+            let mut map = Map::new();
+            for (name, field) in names.into_iter().zip(fields) {
+                map.insert(name.clone(), decode_value(types, field.ty().id(), input)?);
+            }
+            Ok(Value::Object(map))
+        }
+        _ => {
+            let mut values = fields
+                .iter()
+                .map(|field| decode_value(types, field.ty().id(), input))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(match values.len() {
+                0 => Value::Null,
+                1 => values.remove(0),
+                _ => Value::Array(values),
+            })
+        }
+    }
+}
+
+fn decode_value(types: &PortableRegistry, id: u32, input: &mut &[u8]) -> Result<Value> {
+    let ty = resolve(types, id)?;
+    if ty
+        .path()
+        .segments()
+        .last()
+        .is_some_and(|name| name == "AccountId32")
+    {
+        let account = AccountId32::new(<[u8; 32]>::decode(input)?);
+        return Ok(json!(account.to_string()));
+    }
+    Ok(match ty.type_def() {
+        TypeDef::Composite(def) => decode_fields(types, def.fields(), input)?,
+        TypeDef::Variant(def) => {
+            let index = u8::decode(input)?;
+            let variant = def
+                .variants()
+                .iter()
+                .find(|variant| variant.index() == index)
+                .ok_or_else(|| anyhow!("No variant {index} of type {id}"))?;
+            match variant.fields() {
+                [] => json!(variant.name()),
+                fields => {
+                    let value = decode_fields(types, fields, input)?;
+                    Value::Object(Map::from_iter([(variant.name().clone(), value)]))
+                }
+            }
+        }
+        TypeDef::Sequence(def) => {
+            let Compact(len) = Compact::<u32>::decode(input)?;
+            decode_items(types, def.type_param().id(), len, input)?
+        }
+        TypeDef::Array(def) => decode_items(types, def.type_param().id(), def.len(), input)?,
+        TypeDef::Tuple(def) => Value::Array(
+            def.fields()
+                .iter()
+                .map(|ty| decode_value(types, ty.id(), input))
+                .collect::<Result<_>>()?,
+        ),
+        TypeDef::Primitive(primitive) => decode_primitive(primitive, input)?,
+        TypeDef::Compact(_) => unsigned(Compact::<u128>::decode(input)?.0),
+        _ => bail!("Type {id} is not supported"),
+    })
+}
+
+/// `len` items of type `id`, as a hex string for bytes.
+fn decode_items(types: &PortableRegistry, id: u32, len: u32, input: &mut &[u8]) -> Result<Value> {
+    if let TypeDef::Primitive(TypeDefPrimitive::U8) = resolve(types, id)?.type_def() {
+        let len = len as usize;
+        ensure!(input.len() >= len, "Not enough data for {len} bytes");
+        let (bytes, rest) = input.split_at(len);
+        *input = rest;
+        return Ok(json!(format!("0x{}", hex::encode(bytes))));
+    }
+    (0..len)
+        .map(|_| decode_value(types, id, input))
+        .collect::<Result<_>>()
+        .map(Value::Array)
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value> {
+    Ok(match primitive {
+        TypeDefPrimitive::Bool => json!(bool::decode(input)?),
+        TypeDefPrimitive::Str => json!(String::decode(input)?),
+        TypeDefPrimitive::U8 => json!(u8::decode(input)?),
+        TypeDefPrimitive::U16 => json!(u16::decode(input)?),
+        TypeDefPrimitive::U32 => json!(u32::decode(input)?),
+        TypeDefPrimitive::U64 => json!(u64::decode(input)?),
+        TypeDefPrimitive::U128 => unsigned(u128::decode(input)?),
+        TypeDefPrimitive::I8 => json!(i8::decode(input)?),
+        TypeDefPrimitive::I16 => json!(i16::decode(input)?),
+        TypeDefPrimitive::I32 => json!(i32::decode(input)?),
+        TypeDefPrimitive::I64 => json!(i64::decode(input)?),
+        TypeDefPrimitive::I128 => {
+            let value = i128::decode(input)?;
+            i64::try_from(value).map_or_else(|_| json!(value.to_string()), |value| json!(value))
+        }
+        primitive => bail!("{primitive:?} is not supported"),
+    })
+}
+
+/// Values beyond `u64` are strings, JSON numbers can't hold them.
+fn unsigned(value: u128) -> Value {
+    u64::try_from(value).map_or_else(|_| json!(value.to_string()), |value| json!(value))
+}
@@ -0,0 +1,1095 @@
+/// Runtime glue shared by the runtimes, whose generated APIs expose the same
+/// calls and types under different module paths. Expands to the runtime's
+/// `App` and a `glue` module built against the API at the given path under the
+/// crate root, e.g. `deip_glue!(runtime::master_v104::api)`, followed by the
+/// runtime-specific event pallets and the sibling functions describing them.
+macro_rules! deip_glue {
+    ($($api:ident)::+ $(, $pallet:ident => $describe:ident)* $(,)?) => {
+        pub struct App {
+            pub client: crate::$($api)::+::RuntimeApi<
+                subxt::DefaultConfig,
+                crate::app::ExtrinsicParams,
+            >,
+            events: crate::app::EventLog,
+            calls: crate::app::CallRecorder,
+            wait: crate::cli::WaitFor,
+        }
+
+        mod glue {
+            use anyhow::{anyhow, bail, ensure, Result};
+            use parity_scale_codec::Decode;
+            use serde_json::json;
+            use subxt::{
+                sp_core::H256, sp_runtime::AccountId32, Client, DefaultConfig, EventDetails,
+                Metadata,
+            };
+
+            use super::App;
+            use crate::{
+                app::{
+                    describe_module_error, key_matches, AssetInfo, CallRecorder, DeipRuntime,
+                    EventLog, ExtrinsicParams, MapKeys, VestingPlan, VESTING_LOCK_ID,
+                },
+                cli::{
+                    self, Account, AuthorityChange, ContractTerms, Decision, DeipAccount, DeipCall,
+                    DeipContractCall, DeipDaoCall, DeipDaoStorage, DeipProjectCall,
+                    DeipProposalCall, DeipProposalStorage, DeipStorage, DeipVestingCall, Suri,
+                    WaitFor,
+                },
+                error::ErrorKind,
+                output,
+                proposal::{build_batch, record_calls},
+                scenario::CommandLine,
+                $($api)::+::{
+                    deip::{storage::ContractAgreementIdByType, Event as DeipEvent},
+                    runtime_types::{
+                        self,
+                        appchain_deip_runtime::{deip_account::DeipAccountId, Call},
+                        frame_system::pallet::{Call as SystemCall, Event as SystemEvent},
+                        pallet_assets::types::AssetDetails,
+                        pallet_balances::pallet::Event as BalancesEvent,
+                        pallet_deip::{
+                            contract::{Agreement, GenericContractStatus, IndexTerms, Terms},
+                            ProjectContentType,
+                        },
+                        pallet_deip_dao::pallet::{
+                            dao::{AlterAuthority, Dao, InputAuthority},
+                            Call as DaoCall, Event as DaoEvent,
+                        },
+                        pallet_deip_proposal::{
+                            pallet::Event as ProposalEvent,
+                            proposal::{
+                                BatchItem, DeipProposal, ProposalMemberDecision, ProposalState,
+                            },
+                        },
+                        pallet_deip_vesting::pallet::VestingPlan as PalletVestingPlan,
+                        pallet_sudo::pallet::Event as SudoEvent,
+                        primitive_types::H160,
+                        sp_runtime::DispatchError,
+                    },
+                    Event, RuntimeApi,
+                },
+            };
+
+            pub(super) fn new(client: Client<DefaultConfig>, wait: WaitFor) -> App {
+                App {
+                    client: client.to_runtime_api::<RuntimeApi<DefaultConfig, ExtrinsicParams>>(),
+                    events: EventLog::default(),
+                    calls: CallRecorder::default(),
+                    wait,
+                }
+            }
+
+            pub(super) async fn asset(app: &App, id: u32) -> Result<Option<AssetInfo>> {
+                let details = app.client.storage().assets().asset(&id, None).await?;
+                Ok(details.map(asset_info))
+            }
+
+            pub(super) async fn assets(app: &App) -> Result<Vec<(u32, AssetInfo)>> {
+                let keys = MapKeys::new(app.client(), "Assets", "Asset")?;
+                let mut key_iter = app.client.storage().assets().asset_iter(None).await?;
+                let mut assets = Vec::new();
+                while let Some((storage_key, details)) = key_iter.next().await? {
+                    let id: u32 = keys.read(&storage_key.0).next_key()?;
+                    assets.push((id, asset_info(details)));
+                }
+                assets.sort_by_key(|(id, _)| *id);
+                Ok(assets)
+            }
+
+            pub(super) async fn timestamp(app: &App) -> Result<u64> {
+                Ok(app.client.storage().timestamp().now(None).await?)
+            }
+
+            pub(super) fn set_code(code: Vec<u8>) -> Call {
+                Call::System(SystemCall::set_code { code })
+            }
+
+            pub(super) async fn sudo_unchecked_weight(
+                app: &App,
+                signer: Suri,
+                call: Call,
+            ) -> Result<()> {
+                let tx = app.client.tx().sudo().sudo_unchecked_weight(call, 0)?;
+                let start = app.events.count();
+                app.submit(tx, signer).await?;
+                if app.events.logged_since(start, "System::CodeUpdated") {
+                    info!("Sudo: runtime upgrade finished\n");
+                }
+                Ok(())
+            }
+
+            pub(super) async fn deip_tx(app: &App, call: DeipCall) -> Result<()> {
+                match call {
+                    DeipCall::Project { call } => deip_project_tx(app, call).await,
+                    DeipCall::Contract { call } => deip_contract_tx(app, call).await,
+                }
+            }
+
+            pub(super) async fn deip_storage(app: &App, storage: DeipStorage) -> Result<()> {
+                let deip = app.client.storage().deip();
+                match storage {
+                    DeipStorage::ContractAgreementMap { id: Some(id) } => {
+                        match deip.contract_agreement_map(&H160(id.0), None).await? {
+                            Agreement::None => bail!("Contract agreement {id:?} not found"),
+                            agreement => display_agreement(&agreement)?,
+                        }
+                    }
+                    DeipStorage::ContractAgreementMap { id: None } => {
+                        let mut key_iter = deip.contract_agreement_map_iter(None).await?;
+                        while let Some((_, agreement)) = key_iter.next().await? {
+                            display_agreement(&agreement)?;
+                        }
+                    }
+                    DeipStorage::ContractAgreementIdByType {
+                        terms: Some(terms),
+                        id: Some(id),
+                    } => {
+                        // The value is `()`, only a raw fetch tells a missing entry apart.
+                        let entry = ContractAgreementIdByType(&index_terms(terms), &H160(id.0));
+                        match app.client().storage().fetch(&entry, None).await? {
+                            Some(()) => display_agreement_id(terms, id)?,
+                            None => bail!("Contract agreement {id:?} is not indexed by {terms:?}"),
+                        }
+                    }
+                    DeipStorage::ContractAgreementIdByType { terms, id } => {
+                        let keys = MapKeys::new(app.client(), "Deip", "ContractAgreementIdByType")?;
+                        let mut key_iter = deip.contract_agreement_id_by_type_iter(None).await?;
+                        while let Some((storage_key, ())) = key_iter.next().await? {
+                            let mut key = keys.read(&storage_key.0);
+                            let key_terms = match key.next_key()? {
+                                IndexTerms::GenericContractAgreement => {
+                                    ContractTerms::GenericContractAgreement
+                                }
+                            };
+                            let key_id: H160 = key.next_key()?;
+                            if key_matches(&terms, &key_terms) && key_matches(&id, &h160(&key_id)) {
+                                display_agreement_id(key_terms, h160(&key_id))?;
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            pub(super) async fn deip_proposal_tx(app: &App, call: DeipProposalCall) -> Result<()> {
+                let proposals = app.client.tx().deip_proposal();
+                match call {
+                    DeipProposalCall::Propose {
+                        signer,
+                        id,
+                        calls,
+                        batch,
+                    } => {
+                        let batch = build_batch(app, calls, batch.as_deref())
+                            .await?
+                            .into_iter()
+                            .map(|(account, call)| {
+                                let call = Call::decode(&mut call.as_slice())
+                                    .map_err(ErrorKind::Encoding.wrap())?;
+                                Ok(BatchItem {
+                                    account: deip_account(account),
+                                    call,
+                                })
+                            })
+                            .collect::<Result<_>>()?;
+                        let tx = proposals.propose(batch, id.map(|id| H160(id.0)))?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipProposalCall::Decide {
+                        signer,
+                        id,
+                        decision,
+                        weight,
+                    } => {
+                        let weight = match weight {
+                            Some(weight) => weight,
+                            None => {
+                                let storage = app.client.storage().deip_proposal();
+                                storage
+                                    .proposal_repository(&H160(id.0), None)
+                                    .await?
+                                    .ok_or_else(|| anyhow!("Proposal {id:?} not found"))?
+                                    .batch_weight
+                            }
+                        };
+                        let decision = match decision {
+                            Decision::Approve => ProposalMemberDecision::Approve,
+                            Decision::Reject => ProposalMemberDecision::Reject,
+                        };
+                        let tx = proposals.decide(H160(id.0), decision, weight)?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipProposalCall::Expire { signer, id } => {
+                        app.submit(proposals.expire(H160(id.0))?, signer).await
+                    }
+                }
+            }
+
+            pub(super) async fn deip_proposal_storage(
+                app: &App,
+                storage: DeipProposalStorage,
+            ) -> Result<()> {
+                let proposals = app.client.storage().deip_proposal();
+                match storage {
+                    DeipProposalStorage::ProposalRepository { id: Some(id) } => {
+                        match proposals.proposal_repository(&H160(id.0), None).await? {
+                            Some(proposal) => display_proposal(app, &proposal)?,
+                            None => bail!("Proposal {id:?} not found"),
+                        }
+                    }
+                    DeipProposalStorage::ProposalRepository { id: None } => {
+                        let mut key_iter = proposals.proposal_repository_iter(None).await?;
+                        while let Some((_, proposal)) = key_iter.next().await? {
+                            display_proposal(app, &proposal)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            pub(super) async fn deip_dao_tx(app: &App, call: DeipDaoCall) -> Result<()> {
+                let daos = app.client.tx().deip_dao();
+                match call {
+                    DeipDaoCall::Create {
+                        signer,
+                        id,
+                        signatories,
+                        threshold,
+                        metadata,
+                    } => {
+                        let authority = input_authority(signatories, threshold);
+                        app.submit(daos.create(H160(id.0), authority, metadata)?, signer)
+                            .await
+                    }
+                    DeipDaoCall::AlterAuthority {
+                        signer,
+                        dao,
+                        change,
+                    } => {
+                        let authority = match change {
+                            AuthorityChange::AddMember {
+                                member,
+                                preserve_threshold,
+                            } => AlterAuthority::AddMember {
+                                member: member.0,
+                                preserve_threshold,
+                            },
+                            AuthorityChange::RemoveMember {
+                                member,
+                                preserve_threshold,
+                            } => AlterAuthority::RemoveMember {
+                                member: member.0,
+                                preserve_threshold,
+                            },
+                            AuthorityChange::Replace {
+                                authority_key,
+                                signatories,
+                                threshold,
+                            } => AlterAuthority::ReplaceAuthority {
+                                authority_key: authority_key.0,
+                                authority: input_authority(signatories, threshold),
+                            },
+                        };
+                        let call = Call::DeipDao(DaoCall::alter_authority { authority });
+                        app.submit(daos.on_behalf(H160(dao.0), call)?, signer).await
+                    }
+                    DeipDaoCall::Update {
+                        signer,
+                        dao,
+                        metadata,
+                    } => {
+                        let call = Call::DeipDao(DaoCall::update_dao {
+                            new_metadata: metadata,
+                        });
+                        app.submit(daos.on_behalf(H160(dao.0), call)?, signer).await
+                    }
+                    DeipDaoCall::OnBehalf {
+                        signer,
+                        dao,
+                        call,
+                        run,
+                    } => {
+                        let call = match (call, run) {
+                            (Some(call), _) => call,
+                            (None, Some(run)) => {
+                                let mut calls = record_calls(app, &CommandLine::Line(run)).await?;
+                                ensure!(calls.len() == 1, "expected one call, got {}", calls.len());
+                                calls.remove(0).1
+                            }
+                            (None, None) => bail!("expected --call or --run"),
+                        };
+                        let call = Call::decode(&mut call.as_slice())
+                            .map_err(ErrorKind::Encoding.wrap())?;
+                        app.submit(daos.on_behalf(H160(dao.0), call)?, signer).await
+                    }
+                }
+            }
+
+            pub(super) async fn deip_dao_storage(app: &App, storage: DeipDaoStorage) -> Result<()> {
+                let daos = app.client.storage().deip_dao();
+                match storage {
+                    DeipDaoStorage::DaoRepository { id: Some(id) } => {
+                        match daos.dao_repository(&H160(id.0), None).await? {
+                            Some(dao) => display_dao(&dao)?,
+                            None => bail!("DAO {id:?} not found"),
+                        }
+                    }
+                    DeipDaoStorage::DaoRepository { id: None } => {
+                        let mut key_iter = daos.dao_repository_iter(None).await?;
+                        while let Some((_, dao)) = key_iter.next().await? {
+                            display_dao(&dao)?;
+                        }
+                    }
+                    DeipDaoStorage::DaoLookup {
+                        account: Some(account),
+                    } => {
+                        let id = daos
+                            .dao_lookup(&account.0, None)
+                            .await?
+                            .ok_or_else(|| anyhow!("No DAO with account {}", account.0))?;
+                        match daos.dao_repository(&id, None).await? {
+                            Some(dao) => display_dao(&dao)?,
+                            None => bail!("DAO {:?} not found", h160(&id)),
+                        }
+                    }
+                    DeipDaoStorage::DaoLookup { account: None } => {
+                        let keys = MapKeys::new(app.client(), "DeipDao", "DaoLookup")?;
+                        let mut key_iter = daos.dao_lookup_iter(None).await?;
+                        while let Some((storage_key, id)) = key_iter.next().await? {
+                            let account: AccountId32 = keys.read(&storage_key.0).next_key()?;
+                            display_dao_lookup(&account, h160(&id))?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            pub(super) async fn vesting_plan(
+                app: &App,
+                account: &AccountId32,
+            ) -> Result<Option<VestingPlan>> {
+                let storage = app.client.storage().deip_vesting();
+                let plan = storage.vesting_plans(account, None).await?;
+                Ok(plan.map(vesting_plan_info))
+            }
+
+            pub(super) async fn vesting_lock(app: &App, account: &AccountId32) -> Result<u128> {
+                let locks = app.client.storage().balances().locks(account, None).await?;
+                let lock = locks.0.into_iter().find(|lock| lock.id == VESTING_LOCK_ID);
+                Ok(lock.map_or(0, |lock| lock.amount))
+            }
+
+            pub(super) async fn deip_vesting_tx(app: &App, call: DeipVestingCall) -> Result<()> {
+                let vesting = app.client.tx().deip_vesting();
+                match call {
+                    DeipVestingCall::VestedTransfer {
+                        signer,
+                        target,
+                        start,
+                        cliff,
+                        duration,
+                        release_interval,
+                        amount,
+                        initial,
+                        vesting_during_cliff,
+                    } => {
+                        let start_time = match start {
+                            Some(start) => start,
+                            None => app.timestamp().await?,
+                        };
+                        let plan = PalletVestingPlan {
+                            start_time,
+                            cliff_duration: cliff,
+                            total_duration: duration,
+                            interval: release_interval,
+                            initial_amount: initial,
+                            total_amount: amount,
+                            vesting_during_cliff,
+                        };
+                        let tx = vesting.vested_transfer(target.into(), plan)?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipVestingCall::Unlock { signer } => {
+                        app.submit(vesting.unlock()?, signer).await
+                    }
+                }
+            }
+
+            async fn deip_project_tx(app: &App, call: DeipProjectCall) -> Result<()> {
+                let deip = app.client.tx().deip();
+                match call {
+                    DeipProjectCall::Create {
+                        signer,
+                        id,
+                        team,
+                        description,
+                        domains,
+                        private,
+                    } => {
+                        let tx = deip.create_project(
+                            private,
+                            H160(id.0),
+                            deip_account(team),
+                            description,
+                            h160_list(domains),
+                        )?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipProjectCall::Update {
+                        signer,
+                        id,
+                        description,
+                        private,
+                    } => {
+                        let tx = deip.update_project(H160(id.0), description, private)?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipProjectCall::Content {
+                        signer,
+                        id,
+                        project,
+                        team,
+                        content_type,
+                        description,
+                        content,
+                        authors,
+                        references,
+                    } => {
+                        let tx = deip.create_project_content(
+                            H160(id.0),
+                            H160(project.0),
+                            deip_account(team),
+                            project_content_type(content_type),
+                            description,
+                            content,
+                            authors.into_iter().map(deip_account).collect(),
+                            references.map(h160_list),
+                        )?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipProjectCall::Review {
+                        signer,
+                        id,
+                        author,
+                        content,
+                        domains,
+                        assessment_model,
+                        weight,
+                        project_content,
+                    } => {
+                        let tx = deip.create_review(
+                            H160(id.0),
+                            deip_account(author),
+                            content,
+                            h160_list(domains),
+                            assessment_model,
+                            weight,
+                            H160(project_content.0),
+                        )?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipProjectCall::Upvote {
+                        signer,
+                        review,
+                        domain,
+                    } => {
+                        let tx = deip.upvote_review(H160(review.0), H160(domain.0))?;
+                        app.submit(tx, signer).await
+                    }
+                }
+            }
+
+            async fn deip_contract_tx(app: &App, call: DeipContractCall) -> Result<()> {
+                let deip = app.client.tx().deip();
+                match call {
+                    DeipContractCall::Create {
+                        signer,
+                        id,
+                        creator,
+                        parties,
+                        hash,
+                        activation_time,
+                        expiration_time,
+                        terms,
+                    } => {
+                        let tx = deip.create_contract_agreement(
+                            H160(id.0),
+                            deip_account(creator),
+                            parties.into_iter().map(deip_account).collect(),
+                            hash,
+                            activation_time,
+                            expiration_time,
+                            contract_terms(terms),
+                        )?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipContractCall::Accept { signer, id, party } => {
+                        let tx = deip.accept_contract_agreement(H160(id.0), deip_account(party))?;
+                        app.submit(tx, signer).await
+                    }
+                    DeipContractCall::Reject { signer, id, party } => {
+                        let tx = deip.reject_contract_agreement(H160(id.0), deip_account(party))?;
+                        app.submit(tx, signer).await
+                    }
+                }
+            }
+
+            pub(super) fn h160(id: &H160) -> subxt::sp_core::H160 {
+                subxt::sp_core::H160(id.0)
+            }
+
+            fn h160_list(ids: Vec<subxt::sp_core::H160>) -> Vec<H160> {
+                ids.into_iter().map(|id| H160(id.0)).collect()
+            }
+
+            fn deip_account(account: DeipAccount) -> DeipAccountId<AccountId32, H160> {
+                match account {
+                    DeipAccount::Native(account) => DeipAccountId::Native(account),
+                    DeipAccount::Dao(dao) => DeipAccountId::Dao(H160(dao.0)),
+                }
+            }
+
+            fn project_content_type(content_type: cli::ProjectContentType) -> ProjectContentType {
+                match content_type {
+                    cli::ProjectContentType::Announcement => ProjectContentType::Announcement,
+                    cli::ProjectContentType::FinalResult => ProjectContentType::FinalResult,
+                    cli::ProjectContentType::MilestoneArticle => {
+                        ProjectContentType::MilestoneArticle
+                    }
+                    cli::ProjectContentType::MilestoneBook => ProjectContentType::MilestoneBook,
+                    cli::ProjectContentType::MilestoneChapter => {
+                        ProjectContentType::MilestoneChapter
+                    }
+                    cli::ProjectContentType::MilestoneCode => ProjectContentType::MilestoneCode,
+                    cli::ProjectContentType::MilestoneConferencePaper => {
+                        ProjectContentType::MilestoneConferencePaper
+                    }
+                    cli::ProjectContentType::MilestoneCoverPage => {
+                        ProjectContentType::MilestoneCoverPage
+                    }
+                    cli::ProjectContentType::MilestoneData => ProjectContentType::MilestoneData,
+                    cli::ProjectContentType::MilestoneExperimentFindings => {
+                        ProjectContentType::MilestoneExperimentFindings
+                    }
+                    cli::ProjectContentType::MilestoneMethod => ProjectContentType::MilestoneMethod,
+                    cli::ProjectContentType::MilestoneNegativeResults => {
+                        ProjectContentType::MilestoneNegativeResults
+                    }
+                    cli::ProjectContentType::MilestonePatent => ProjectContentType::MilestonePatent,
+                    cli::ProjectContentType::MilestonePoster => ProjectContentType::MilestonePoster,
+                    cli::ProjectContentType::MilestonePreprint => {
+                        ProjectContentType::MilestonePreprint
+                    }
+                    cli::ProjectContentType::MilestonePresentation => {
+                        ProjectContentType::MilestonePresentation
+                    }
+                    cli::ProjectContentType::MilestoneRawData => {
+                        ProjectContentType::MilestoneRawData
+                    }
+                    cli::ProjectContentType::MilestoneResearchProposal => {
+                        ProjectContentType::MilestoneResearchProposal
+                    }
+                    cli::ProjectContentType::MilestoneTechnicalReport => {
+                        ProjectContentType::MilestoneTechnicalReport
+                    }
+                    cli::ProjectContentType::MilestoneThesis => ProjectContentType::MilestoneThesis,
+                }
+            }
+
+            fn contract_terms(terms: ContractTerms) -> Terms {
+                match terms {
+                    ContractTerms::GenericContractAgreement => Terms::GenericContractAgreement,
+                }
+            }
+
+            fn index_terms(terms: ContractTerms) -> IndexTerms {
+                match terms {
+                    ContractTerms::GenericContractAgreement => IndexTerms::GenericContractAgreement,
+                }
+            }
+
+            type AgreementRecord = Agreement<AccountId32, H256, u64>;
+
+            fn display_agreement(agreement: &AgreementRecord) -> Result<()> {
+                let (status, contract, accepted_by) = match agreement {
+                    Agreement::None => return Ok(()),
+                    Agreement::GenericContract(GenericContractStatus::PartiallyAccepted {
+                        contract,
+                        accepted_by,
+                    }) => ("partially_accepted", contract, accepted_by.clone()),
+                    Agreement::GenericContract(GenericContractStatus::Accepted(contract)) => {
+                        ("accepted", contract, contract.parties.clone())
+                    }
+                    Agreement::GenericContract(GenericContractStatus::Rejected(contract)) => {
+                        ("rejected", contract, Vec::new())
+                    }
+                };
+                let record = json!({
+                    "id": h160(&contract.id),
+                    "status": status,
+                    "creator": contract.creator,
+                    "parties": contract.parties,
+                    "accepted_by": accepted_by,
+                    "hash": contract.hash,
+                    "activation_time": contract.activation_time,
+                    "expiration_time": contract.expiration_time,
+                });
+                output::emit(&record, |_| {
+                    let parties: Vec<_> =
+                        contract.parties.iter().map(ToString::to_string).collect();
+                    info!(
+                        "Contract agreement {:?}: {status}, {}/{} accepted, creator {}, parties {}, hash {:?}",
+                        h160(&contract.id),
+                        accepted_by.len(),
+                        contract.parties.len(),
+                        contract.creator,
+                        parties.join(", "),
+                        contract.hash,
+                    )
+                })
+            }
+
+            fn display_agreement_id(terms: ContractTerms, id: subxt::sp_core::H160) -> Result<()> {
+                output::emit(&json!({ "terms": format!("{terms:?}"), "id": id }), |_| {
+                    info!("Contract agreement {id:?} by {terms:?}")
+                })
+            }
+
+            fn describe_deip_event(event: DeipEvent) -> String {
+                match event {
+                    DeipEvent::ProjectCreated(owner, project) => format!(
+                        "Deip::ProjectCreated: {:?} by {owner}",
+                        h160(&project.external_id),
+                    ),
+                    DeipEvent::ProjectRemoved(owner, project) => format!(
+                        "Deip::ProjectRemoved: {:?} by {owner}",
+                        h160(&project.external_id),
+                    ),
+                    DeipEvent::ProjectUpdated(owner, id) => {
+                        format!("Deip::ProjectUpdated: {:?} by {owner}", h160(&id))
+                    }
+                    DeipEvent::ProjectContnetCreated(owner, id) => {
+                        format!("Deip::ProjectContnetCreated: {:?} by {owner}", h160(&id))
+                    }
+                    DeipEvent::ReviewCreated(owner, review) => format!(
+                        "Deip::ReviewCreated: {:?} by {owner} of content {:?}",
+                        h160(&review.external_id),
+                        h160(&review.project_content_external_id),
+                    ),
+                    DeipEvent::ReviewUpvoted(review, voter, domain) => format!(
+                        "Deip::ReviewUpvoted: {:?} by {voter} in domain {:?}",
+                        h160(&review),
+                        h160(&domain),
+                    ),
+                    DeipEvent::ContractAgreementCreated(id) => {
+                        format!("Deip::ContractAgreementCreated: {:?}", h160(&id))
+                    }
+                    DeipEvent::ContractAgreementAccepted(id, party) => {
+                        format!(
+                            "Deip::ContractAgreementAccepted: {:?} by {party}",
+                            h160(&id)
+                        )
+                    }
+                    DeipEvent::ContractAgreementFinalized(id) => {
+                        format!("Deip::ContractAgreementFinalized: {:?}", h160(&id))
+                    }
+                    DeipEvent::ContractAgreementRejected(id, party) => {
+                        format!(
+                            "Deip::ContractAgreementRejected: {:?} by {party}",
+                            h160(&id)
+                        )
+                    }
+                    event => format!("Deip::{event:?}"),
+                }
+            }
+
+            type ProposalRecord = DeipProposal<runtime_types::appchain_deip_runtime::Runtime>;
+
+            fn display_proposal(app: &App, proposal: &ProposalRecord) -> Result<()> {
+                let state =
+                    describe_proposal_state(&app.client().metadata().read(), &proposal.state);
+                let decisions: Vec<_> = proposal
+                    .decisions
+                    .iter()
+                    .map(|(account, decision)| (account, format!("{decision:?}")))
+                    .collect();
+                let batch: Vec<_> = proposal
+                    .batch
+                    .iter()
+                    .map(|item| (&item.account, format!("{:?}", item.call)))
+                    .collect();
+                let record = json!({
+                    "id": h160(&proposal.id),
+                    "author": proposal.author,
+                    "state": state,
+                    "batch_weight": proposal.batch_weight,
+                    "created_at": proposal.created_at,
+                    "decisions": decisions,
+                    "batch": batch,
+                });
+                output::emit(&record, |_| {
+                    info!(
+                        "Proposal {:?} by {}: {state}, weight {}, created at {}",
+                        h160(&proposal.id),
+                        proposal.author,
+                        proposal.batch_weight,
+                        proposal.created_at,
+                    );
+                    for (account, decision) in &decisions {
+                        info!(" {account}: {decision}");
+                    }
+                    for (account, call) in &batch {
+                        info!(" - as {account}: {call}");
+                    }
+                })
+            }
+
+            fn describe_proposal_state(metadata: &Metadata, state: &ProposalState) -> String {
+                match state {
+                    ProposalState::Failed(error) => {
+                        let error = describe_dispatch_error(metadata, error.clone());
+                        format!("Failed: {error}")
+                    }
+                    state => format!("{state:?}"),
+                }
+            }
+
+            fn describe_proposal_event(metadata: &Metadata, event: ProposalEvent) -> String {
+                match event {
+                    ProposalEvent::Proposed {
+                        author,
+                        batch,
+                        proposal_id,
+                        batch_weight,
+                    } => format!(
+                        "DeipProposal::Proposed: {:?} by {author}, {} calls, weight {batch_weight}",
+                        h160(&proposal_id),
+                        batch.len(),
+                    ),
+                    ProposalEvent::Approved {
+                        member,
+                        proposal_id,
+                    } => format!(
+                        "DeipProposal::Approved: {:?} by {member}",
+                        h160(&proposal_id)
+                    ),
+                    ProposalEvent::RevokedApproval {
+                        member,
+                        proposal_id,
+                    } => format!(
+                        "DeipProposal::RevokedApproval: {:?} by {member}",
+                        h160(&proposal_id)
+                    ),
+                    ProposalEvent::Resolved {
+                        member,
+                        proposal_id,
+                        state,
+                    } => format!(
+                        "DeipProposal::Resolved: {:?} by {member}, {}",
+                        h160(&proposal_id),
+                        describe_proposal_state(metadata, &state),
+                    ),
+                    ProposalEvent::Expired { proposal_id } => {
+                        format!("DeipProposal::Expired: {:?}", h160(&proposal_id))
+                    }
+                }
+            }
+
+            fn input_authority(
+                signatories: Vec<Account>,
+                threshold: u16,
+            ) -> InputAuthority<AccountId32> {
+                InputAuthority {
+                    signatories: signatories.into_iter().map(|account| account.0).collect(),
+                    threshold,
+                }
+            }
+
+            type DaoRecord = Dao<AccountId32, H160>;
+
+            fn display_dao(dao: &DaoRecord) -> Result<()> {
+                let record = json!({
+                    "id": h160(&dao.id),
+                    "account": dao.dao_key,
+                    "authority_key": dao.authority_key,
+                    "signatories": dao.authority.signatories,
+                    "threshold": dao.authority.threshold,
+                    "metadata": dao.metadata,
+                });
+                output::emit(&record, |_| {
+                    let signatories: Vec<_> = dao
+                        .authority
+                        .signatories
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect();
+                    info!(
+                        "DAO {:?}: account {}, authority {} ({} of {}), metadata {:?}",
+                        h160(&dao.id),
+                        dao.dao_key,
+                        dao.authority_key,
+                        dao.authority.threshold,
+                        signatories.join(", "),
+                        dao.metadata,
+                    )
+                })
+            }
+
+            fn display_dao_lookup(account: &AccountId32, id: subxt::sp_core::H160) -> Result<()> {
+                output::emit(&json!({ "account": account, "id": id }), |_| {
+                    info!("DAO account {account}: {id:?}")
+                })
+            }
+
+            fn describe_dao_event(event: DaoEvent) -> String {
+                let (variant, dao) = match event {
+                    DaoEvent::DaoCreate(dao) => ("DaoCreate", dao),
+                    DaoEvent::DaoAlterAuthority(dao) => ("DaoAlterAuthority", dao),
+                    DaoEvent::DaoMetadataUpdated(dao) => ("DaoMetadataUpdated", dao),
+                };
+                format!(
+                    "DeipDao::{variant}: {:?}, account {}, authority {} ({} of {} signatories)",
+                    h160(&dao.id),
+                    dao.dao_key,
+                    dao.authority_key,
+                    dao.authority.threshold,
+                    dao.authority.signatories.len(),
+                )
+            }
+
+            fn vesting_plan_info(plan: PalletVestingPlan<u128>) -> VestingPlan {
+                VestingPlan {
+                    start_time: plan.start_time,
+                    cliff_duration: plan.cliff_duration,
+                    total_duration: plan.total_duration,
+                    interval: plan.interval,
+                    initial_amount: plan.initial_amount,
+                    total_amount: plan.total_amount,
+                    vesting_during_cliff: plan.vesting_during_cliff,
+                }
+            }
+
+            fn asset_info(details: AssetDetails<u128, AccountId32, u128>) -> AssetInfo {
+                AssetInfo {
+                    owner: details.owner,
+                    issuer: details.issuer,
+                    admin: details.admin,
+                    freezer: details.freezer,
+                    supply: details.supply,
+                    deposit: details.deposit,
+                    min_balance: details.min_balance,
+                    is_sufficient: details.is_sufficient,
+                    accounts: details.accounts,
+                    sufficients: details.sufficients,
+                    approvals: details.approvals,
+                    is_frozen: details.is_frozen,
+                }
+            }
+
+            pub(super) fn describe_event(
+                metadata: &Metadata,
+                event: EventDetails<Event>,
+            ) -> String {
+                let EventDetails {
+                    pallet,
+                    variant,
+                    event,
+                    ..
+                } = event;
+                match event {
+                    Event::System(SystemEvent::ExtrinsicSuccess(info)) => {
+                        format!("{pallet}::{variant}: {info:?}")
+                    }
+                    Event::System(SystemEvent::ExtrinsicFailed(error, _)) => {
+                        let error = describe_dispatch_error(metadata, error);
+                        format!("{pallet}::{variant}: {error}")
+                    }
+                    Event::System(SystemEvent::CodeUpdated) => format!("{pallet}::{variant}"),
+                    Event::Sudo(SudoEvent::Sudid { sudo_result })
+                    | Event::Sudo(SudoEvent::SudoAsDone { sudo_result }) => match sudo_result {
+                        Ok(()) => format!("{pallet}::{variant}: Ok"),
+                        Err(error) => {
+                            let error = describe_dispatch_error(metadata, error);
+                            format!("{pallet}::{variant}: {error}")
+                        }
+                    },
+                    Event::System(event) => format!("{pallet}::{event:?}"),
+                    Event::Balances(event) => format!("{pallet}::{event:?}"),
+                    Event::OctopusAppchain(event) => format!("{pallet}::{event:?}"),
+                    Event::OctopusLpos(event) => format!("{pallet}::{event:?}"),
+                    Event::OctopusUpwardMessages(event) => format!("{pallet}::{event:?}"),
+                    Event::Session(event) => format!("{pallet}::{event:?}"),
+                    Event::Grandpa(event) => format!("{pallet}::{event:?}"),
+                    Event::Sudo(event) => format!("{pallet}::{event:?}"),
+                    Event::ImOnline(event) => format!("{pallet}::{event:?}"),
+                    Event::Assets(event) => format!("{pallet}::{event:?}"),
+                    Event::Uniques(event) => format!("{pallet}::{event:?}"),
+                    Event::Multisig(event) => format!("{pallet}::{event:?}"),
+                    Event::Utility(event) => format!("{pallet}::{event:?}"),
+                    Event::Deip(event) => describe_deip_event(event),
+                    Event::DeipProposal(event) => describe_proposal_event(metadata, event),
+                    Event::DeipDao(event) => describe_dao_event(event),
+                    Event::DeipVesting(event) => format!("{pallet}::{event:?}"),
+                    Event::DeipInvestmentOpportunity(event) => format!("{pallet}::{event:?}"),
+                    $(Event::$pallet(event) => super::$describe(event),)*
+                }
+            }
+
+            pub(super) fn dispatch_failure(app: &App, event: &Event) -> Option<String> {
+                match event {
+                    Event::System(SystemEvent::ExtrinsicFailed(error, _))
+                    | Event::Sudo(SudoEvent::Sudid {
+                        sudo_result: Err(error),
+                    })
+                    | Event::Sudo(SudoEvent::SudoAsDone {
+                        sudo_result: Err(error),
+                    })
+                    | Event::DeipProposal(ProposalEvent::Resolved {
+                        state: ProposalState::Failed(error),
+                        ..
+                    }) => Some(describe_dispatch_error(
+                        &app.client().metadata().read(),
+                        error.clone(),
+                    )),
+                    _ => None,
+                }
+            }
+
+            pub(super) fn fee_paid(event: &Event, payer: &AccountId32) -> Option<i128> {
+                match event {
+                    Event::Balances(BalancesEvent::Withdraw { who, amount }) if who == payer => {
+                        Some(*amount as i128)
+                    }
+                    Event::Balances(BalancesEvent::Deposit { who, amount }) if who == payer => {
+                        Some(-(*amount as i128))
+                    }
+                    _ => None,
+                }
+            }
+
+            fn describe_dispatch_error(metadata: &Metadata, error: DispatchError) -> String {
+                match error {
+                    DispatchError::Module { index, error } => {
+                        describe_module_error(metadata, index, error)
+                    }
+                    error => format!("{error:?}"),
+                }
+            }
+        }
+    };
+}
+
+/// `DeipRuntime` methods backed by the `App` and `glue` module of `deip_glue!`,
+/// expanded in each `impl DeipRuntime for App`.
+macro_rules! deip_methods {
+    () => {
+        fn new(client: subxt::Client<subxt::DefaultConfig>, wait: crate::cli::WaitFor) -> Self {
+            glue::new(client, wait)
+        }
+
+        fn client(&self) -> &subxt::Client<subxt::DefaultConfig> {
+            &self.client.client
+        }
+
+        fn event_log(&self) -> &crate::app::EventLog {
+            &self.events
+        }
+
+        fn wait(&self) -> crate::cli::WaitFor {
+            self.wait
+        }
+
+        fn call_recorder(&self) -> &crate::app::CallRecorder {
+            &self.calls
+        }
+
+        async fn asset(&self, id: u32) -> anyhow::Result<Option<crate::app::AssetInfo>> {
+            glue::asset(self, id).await
+        }
+
+        async fn assets(&self) -> anyhow::Result<Vec<(u32, crate::app::AssetInfo)>> {
+            glue::assets(self).await
+        }
+
+        async fn timestamp(&self) -> anyhow::Result<u64> {
+            glue::timestamp(self).await
+        }
+
+        fn set_code(code: Vec<u8>) -> Self::Call {
+            glue::set_code(code)
+        }
+
+        async fn sudo_unchecked_weight(
+            &self,
+            signer: crate::cli::Suri,
+            call: Self::Call,
+        ) -> anyhow::Result<()> {
+            glue::sudo_unchecked_weight(self, signer, call).await
+        }
+
+        async fn deip_tx(&self, call: crate::cli::DeipCall) -> anyhow::Result<()> {
+            glue::deip_tx(self, call).await
+        }
+
+        async fn deip_storage(&self, storage: crate::cli::DeipStorage) -> anyhow::Result<()> {
+            glue::deip_storage(self, storage).await
+        }
+
+        async fn deip_proposal_tx(&self, call: crate::cli::DeipProposalCall) -> anyhow::Result<()> {
+            glue::deip_proposal_tx(self, call).await
+        }
+
+        async fn deip_proposal_storage(
+            &self,
+            storage: crate::cli::DeipProposalStorage,
+        ) -> anyhow::Result<()> {
+            glue::deip_proposal_storage(self, storage).await
+        }
+
+        async fn deip_dao_tx(&self, call: crate::cli::DeipDaoCall) -> anyhow::Result<()> {
+            glue::deip_dao_tx(self, call).await
+        }
+
+        async fn deip_dao_storage(
+            &self,
+            storage: crate::cli::DeipDaoStorage,
+        ) -> anyhow::Result<()> {
+            glue::deip_dao_storage(self, storage).await
+        }
+
+        async fn vesting_plan(
+            &self,
+            account: &subxt::sp_runtime::AccountId32,
+        ) -> anyhow::Result<Option<crate::app::VestingPlan>> {
+            glue::vesting_plan(self, account).await
+        }
+
+        async fn vesting_lock(
+            &self,
+            account: &subxt::sp_runtime::AccountId32,
+        ) -> anyhow::Result<u128> {
+            glue::vesting_lock(self, account).await
+        }
+
+        async fn deip_vesting_tx(&self, call: crate::cli::DeipVestingCall) -> anyhow::Result<()> {
+            glue::deip_vesting_tx(self, call).await
+        }
+
+        fn describe_event(
+            &self,
+            metadata: &subxt::Metadata,
+            event: subxt::EventDetails<Self::Event>,
+        ) -> String {
+            glue::describe_event(metadata, event)
+        }
+
+        fn dispatch_failure(&self, event: &Self::Event) -> Option<String> {
+            glue::dispatch_failure(self, event)
+        }
+
+        fn fee_paid(event: &Self::Event, payer: &subxt::sp_runtime::AccountId32) -> Option<i128> {
+            glue::fee_paid(event, payer)
+        }
+    };
+}
@@ -1,124 +1,359 @@
-use anyhow::Result;
-use futures::StreamExt;
-use parity_scale_codec::Decode;
-use sp_keyring::{sr25519::sr25519::Pair, AccountKeyring};
-use subxt::{ClientBuilder, DefaultConfig, EventDetails, PairSigner, PolkadotExtrinsicParams};
-
-use crate::runtime::master_v104::api::{
-    runtime_types::{
-        frame_system::pallet::{Error as SystemError, Event as SystemEvent},
-        pallet_sudo::pallet::Event as SudoEvent,
-        sp_runtime::DispatchError,
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use subxt::sp_core::crypto::Ss58Codec;
+
+use crate::{
+    app::{block_hash, DeipRuntime, MapKeys},
+    cli::{self, AssetsCall, Suri},
+    migration::{AssetState, MetadataState, PreMigration},
+    runtime::master_v104::api::{
+        runtime_types::{
+            appchain_deip_runtime::Call,
+            pallet_assets::{pallet::Call as AssetsPalletCall, types::DestroyWitness},
+            primitive_types::H160,
+            sp_runtime::DispatchError,
+        },
+        Event,
     },
-    Event, RuntimeApi, PALLETS,
 };
 
-pub struct App {
-    pub client: RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>,
-}
+deip_glue!(runtime::master_v104::api);
 
-impl App {
-    pub async fn build() -> Result<Self> {
-        let client = ClientBuilder::new()
-            .build()
-            .await?
-            .to_runtime_api::<RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>>();
-        Ok(Self { client })
-    }
+impl DeipRuntime for App {
+    const NAME: &'static str = "master";
+    const SPEC_VERSION: u32 = 104;
+
+    type Event = Event;
+    type DispatchError = DispatchError;
+    type Call = Call;
+
+    deip_methods!();
 
-    pub async fn spawn_events_listener(&self) {
-        tokio::spawn(async {
-            let client = ClientBuilder::new()
-                .build()
-                .await
-                .unwrap()
-                .to_runtime_api::<RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>>();
-            let mut events_sub = client.events().subscribe().await.unwrap();
-            while let Some(events) = events_sub.next().await {
-                let events = events.unwrap();
-                let hash = events.block_hash();
-                info!("Events in block {hash:?}\n");
-                for event in events.iter() {
-                    let event = event.unwrap();
-                    let event = event.event;
-                    info!("    {event:?}\n");
-                }
-            }
-        });
+    async fn assets_tx(&self, call: AssetsCall) -> Result<()> {
+        let assets = self.client.tx().assets();
+        match call {
+            AssetsCall::Create {
+                signer,
+                id,
+                admin,
+                min_balance,
+            } => {
+                let tx = assets.create(id, admin.into(), min_balance)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::ForceCreate {
+                signer,
+                id,
+                owner,
+                is_sufficient,
+                min_balance,
+            } => {
+                let call = AssetsPalletCall::force_create {
+                    id,
+                    owner: owner.into(),
+                    is_sufficient,
+                    min_balance,
+                };
+                self.sudo(call, signer).await
+            }
+            AssetsCall::Destroy { signer, id } => {
+                let details = self
+                    .client
+                    .storage()
+                    .assets()
+                    .asset(&id, None)
+                    .await?
+                    .ok_or_else(|| anyhow!("Asset {id} does not exist"))?;
+                let witness = DestroyWitness {
+                    accounts: details.accounts,
+                    sufficients: details.sufficients,
+                    approvals: details.approvals,
+                };
+                self.submit(assets.destroy(id, witness)?, signer).await
+            }
+            AssetsCall::Mint {
+                signer,
+                id,
+                beneficiary,
+                amount,
+            } => {
+                let tx = assets.mint(id, beneficiary.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::Burn {
+                signer,
+                id,
+                who,
+                amount,
+            } => {
+                let tx = assets.burn(id, who.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::Transfer {
+                signer,
+                id,
+                target,
+                amount,
+            } => {
+                let tx = assets.transfer(id, target.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::TransferKeepAlive {
+                signer,
+                id,
+                target,
+                amount,
+            } => {
+                let tx = assets.transfer_keep_alive(id, target.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::ForceTransfer {
+                signer,
+                id,
+                source,
+                dest,
+                amount,
+            } => {
+                let call = AssetsPalletCall::force_transfer {
+                    id,
+                    source: source.into(),
+                    dest: dest.into(),
+                    amount,
+                };
+                self.sudo(call, signer).await
+            }
+            AssetsCall::Freeze { signer, id, who } => {
+                let tx = assets.freeze(id, who.into())?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::Thaw { signer, id, who } => {
+                let tx = assets.thaw(id, who.into())?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::FreezeAsset { signer, id } => {
+                self.submit(assets.freeze_asset(id)?, signer).await
+            }
+            AssetsCall::ThawAsset { signer, id } => {
+                self.submit(assets.thaw_asset(id)?, signer).await
+            }
+            AssetsCall::TransferOwnership { signer, id, owner } => {
+                let tx = assets.transfer_ownership(id, owner.into())?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::SetTeam {
+                signer,
+                id,
+                issuer,
+                admin,
+                freezer,
+            } => {
+                let tx = assets.set_team(id, issuer.into(), admin.into(), freezer.into())?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::SetMetadata {
+                signer,
+                id,
+                name,
+                symbol,
+                decimals,
+            } => {
+                let tx =
+                    assets.set_metadata(id, name.into_bytes(), symbol.into_bytes(), decimals)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::ClearMetadata { signer, id } => {
+                self.submit(assets.clear_metadata(id)?, signer).await
+            }
+            AssetsCall::ForceSetMetadata {
+                signer,
+                id,
+                name,
+                symbol,
+                decimals,
+                is_frozen,
+            } => {
+                let call = AssetsPalletCall::force_set_metadata {
+                    id,
+                    name: name.into_bytes(),
+                    symbol: symbol.into_bytes(),
+                    decimals,
+                    is_frozen,
+                };
+                self.sudo(call, signer).await
+            }
+            AssetsCall::ForceClearMetadata { signer, id } => {
+                let call = AssetsPalletCall::force_clear_metadata { id };
+                self.sudo(call, signer).await
+            }
+            AssetsCall::ForceAssetStatus {
+                signer,
+                id,
+                owner,
+                issuer,
+                admin,
+                freezer,
+                min_balance,
+                is_sufficient,
+                is_frozen,
+            } => {
+                let call = AssetsPalletCall::force_asset_status {
+                    id,
+                    owner: owner.into(),
+                    issuer: issuer.into(),
+                    admin: admin.into(),
+                    freezer: freezer.into(),
+                    min_balance,
+                    is_sufficient,
+                    is_frozen,
+                };
+                self.sudo(call, signer).await
+            }
+            AssetsCall::ApproveTransfer {
+                signer,
+                id,
+                delegate,
+                amount,
+            } => {
+                let tx = assets.approve_transfer(id, delegate.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::CancelApproval {
+                signer,
+                id,
+                delegate,
+            } => {
+                let tx = assets.cancel_approval(id, delegate.into())?;
+                self.submit(tx, signer).await
+            }
+            AssetsCall::ForceCancelApproval {
+                signer,
+                id,
+                owner,
+                delegate,
+            } => {
+                let call = AssetsPalletCall::force_cancel_approval {
+                    id,
+                    owner: owner.into(),
+                    delegate: delegate.into(),
+                };
+                self.sudo(call, signer).await
+            }
+            AssetsCall::TransferApproved {
+                signer,
+                id,
+                owner,
+                destination,
+                amount,
+            } => {
+                let tx = assets.transfer_approved(id, owner.into(), destination.into(), amount)?;
+                self.submit(tx, signer).await
+            }
+        }
     }
 
-    pub fn signer(&self, account: AccountKeyring) -> PairSigner<DefaultConfig, Pair> {
-        PairSigner::new(account.pair())
+    async fn capture_migration(&self) -> Result<PreMigration> {
+        let at = block_hash(self.client(), None).await?;
+        let storage = self.client.storage();
+
+        let mut assets = BTreeMap::new();
+        let keys = MapKeys::new(self.client(), "Assets", "Asset")?;
+        let mut key_iter = storage.assets().asset_iter(Some(at)).await?;
+        while let Some((storage_key, details)) = key_iter.next().await? {
+            let id: u32 = keys.read(&storage_key.0).next_key()?;
+            let state = AssetState {
+                owner: details.owner.to_ss58check(),
+                supply: details.supply,
+            };
+            assets.insert(id, state);
+        }
+
+        let mut deip_assets = BTreeMap::new();
+        let keys = MapKeys::new(self.client(), "DeipAssets", "AssetIdByDeipAssetId")?;
+        let mut key_iter = storage
+            .deip_assets()
+            .asset_id_by_deip_asset_id_iter(Some(at))
+            .await?;
+        while let Some((storage_key, ())) = key_iter.next().await? {
+            let mut key = keys.read(&storage_key.0);
+            let deip_id: H160 = key.next_key()?;
+            let id: u32 = key.next_key()?;
+            deip_assets.insert(hex_id(&deip_id), id);
+        }
+
+        let mut ft_balances = BTreeMap::new();
+        let keys = MapKeys::new(self.client(), "DeipAssets", "FtBalanceMap")?;
+        let mut key_iter = storage.deip_assets().ft_balance_map_iter(Some(at)).await?;
+        while let Some((storage_key, holders)) = key_iter.next().await? {
+            let deip_id = hex_id(&keys.read(&storage_key.0).next_key()?);
+            let id = deip_assets.get(&deip_id).ok_or_else(|| {
+                anyhow!("DeipAssets::FtBalanceMap({deip_id}): not in AssetIdByDeipAssetId")
+            })?;
+            let mut balances = BTreeMap::new();
+            for holder in holders {
+                let account = storage.assets().account(id, &holder, Some(at)).await?;
+                balances.insert(holder.to_ss58check(), account.balance);
+            }
+            ft_balances.insert(deip_id, balances);
+        }
+
+        let mut asset_metadata = BTreeMap::new();
+        let keys = MapKeys::new(self.client(), "DeipAssets", "AssetMetadataMap")?;
+        let mut key_iter = storage
+            .deip_assets()
+            .asset_metadata_map_iter(Some(at))
+            .await?;
+        while let Some((storage_key, metadata)) = key_iter.next().await? {
+            let deip_id: H160 = keys.read(&storage_key.0).next_key()?;
+            let state = MetadataState::new(&metadata.name, &metadata.symbol, metadata.decimals);
+            asset_metadata.insert(hex_id(&deip_id), state);
+        }
+
+        let mut nft_classes = BTreeMap::new();
+        let keys = MapKeys::new(self.client(), "DeipUniques", "NftClassIdByDeipNftClassId")?;
+        let mut key_iter = storage
+            .deip_uniques()
+            .nft_class_id_by_deip_nft_class_id_iter(Some(at))
+            .await?;
+        while let Some((storage_key, class_id)) = key_iter.next().await? {
+            let deip_id: H160 = keys.read(&storage_key.0).next_key()?;
+            nft_classes.insert(hex_id(&deip_id), class_id);
+        }
+
+        let mut nft_balances = BTreeMap::new();
+        let keys = MapKeys::new(self.client(), "DeipUniques", "NftBalanceMap")?;
+        let mut key_iter = storage
+            .deip_uniques()
+            .nft_balance_map_iter(Some(at))
+            .await?;
+        while let Some((storage_key, holders)) = key_iter.next().await? {
+            let deip_id: H160 = keys.read(&storage_key.0).next_key()?;
+            let holders = holders.iter().map(Ss58Codec::to_ss58check).collect();
+            nft_balances.insert(hex_id(&deip_id), holders);
+        }
+
+        Ok(PreMigration {
+            block_hash: format!("{at:?}"),
+            assets,
+            next_asset_id: storage.deip_assets().next_asset_id(Some(at)).await?,
+            deip_assets,
+            nft_classes,
+            next_nft_class_id: storage.deip_uniques().next_nft_class_id(Some(at)).await?,
+            ft_balances,
+            asset_metadata,
+            nft_balances,
+        })
     }
 }
 
-pub fn display_event(event: EventDetails<Event>) {
-    let event = event.event;
-    match event {
-        Event::System(event) => match event {
-            SystemEvent::ExtrinsicSuccess(info) => info!(" - Success::{info:?}"),
-            SystemEvent::ExtrinsicFailed(error, _) => match error {
-                DispatchError::Other => todo!(),
-                DispatchError::CannotLookup => todo!(),
-                DispatchError::BadOrigin => todo!(),
-                DispatchError::Module { index, error } => decode_module_error(index, error),
-                DispatchError::ConsumerRemaining => todo!(),
-                DispatchError::NoProviders => todo!(),
-                DispatchError::Token(error) => info!(" - System::Failed::Token::{error:?}"),
-                DispatchError::Arithmetic(_) => todo!(),
-            },
-            SystemEvent::CodeUpdated => info!(" - CodeUpdated"),
-            SystemEvent::KilledAccount(_) => todo!(),
-            SystemEvent::Remarked(_, _) => todo!(),
-            system_event => info!(" - {system_event:?}"),
-        },
-        Event::Balances(event) => info!(" - Balances::{event:?}"),
-        Event::OctopusAppchain(_) => todo!(),
-        Event::OctopusLpos(_) => todo!(),
-        Event::OctopusUpwardMessages(_) => todo!(),
-        Event::Session(_) => todo!(),
-        Event::Grandpa(_) => todo!(),
-        Event::Sudo(event) => match event {
-            SudoEvent::Sudid { sudo_result } => {
-                if let Err(err) = sudo_result {
-                    match err {
-                        DispatchError::Other => todo!(),
-                        DispatchError::CannotLookup => todo!(),
-                        DispatchError::BadOrigin => todo!(),
-                        DispatchError::Module { index, error } => decode_module_error(index, error),
-                        DispatchError::ConsumerRemaining => todo!(),
-                        DispatchError::NoProviders => todo!(),
-                        DispatchError::Token(_) => todo!(),
-                        DispatchError::Arithmetic(_) => todo!(),
-                    }
-                }
-            }
-
-            SudoEvent::KeyChanged { .. } => todo!(),
-            SudoEvent::SudoAsDone { .. } => todo!(),
-        },
-        Event::ImOnline(_) => todo!(),
-        Event::Assets(event) => info!(" - Assets::{event:?}"),
-        Event::Uniques(event) => info!(" - Uniques::{event:?}"),
-        Event::Multisig(_) => todo!(),
-        Event::Utility(_) => todo!(),
-        Event::Deip(_) => todo!(),
-        Event::DeipProposal(_) => todo!(),
-        Event::DeipDao(_) => todo!(),
-        Event::DeipVesting(_) => todo!(),
-        Event::DeipInvestmentOpportunity(_) => todo!(),
+impl App {
+    /// Dispatches a root-only Assets call through `Sudo::sudo`.
+    async fn sudo(&self, call: AssetsPalletCall, signer: Suri) -> Result<()> {
+        let tx = self.client.tx().sudo().sudo(Call::Assets(call))?;
+        self.submit(tx, signer).await
     }
 }
 
-fn decode_module_error(index: u8, error: u8) {
-    match index {
-        0 => {
-            let error = SystemError::decode(&mut [error].as_ref()).unwrap();
-            info!(
-                " - {}::Sudid::Err::Module::{error:?}",
-                PALLETS[index as usize]
-            );
-        }
-        index => panic!(" - Unknown index: {index}"),
-    }
+/// Deip id as stored in `PreMigration`.
+fn hex_id(id: &H160) -> String {
+    format!("0x{}", hex::encode(id.0))
 }
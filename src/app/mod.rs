@@ -1,2 +1,645 @@
+#[macro_use]
+mod glue;
+
 pub mod develop;
+mod fields;
 pub mod master;
+
+use anyhow::{anyhow, bail, Result};
+use frame_metadata::{RuntimeMetadataPrefixed, StorageEntryType, StorageHasher};
+use parity_scale_codec::{Decode, Encode};
+use serde::Serialize;
+use serde_json::Value;
+use sp_keyring::sr25519::sr25519::Pair;
+use std::{fmt::Debug, sync::Mutex};
+use subxt::{
+    events::{EventSub, EventSubscription, FinalizedEventSub},
+    rpc::rpc_params,
+    sp_core::{Bytes, H256},
+    sp_runtime::AccountId32,
+    Client, ClientBuilder, Config, DefaultConfig, EventDetails, HasModuleError, Metadata,
+    PairSigner, PolkadotExtrinsicParams, Signer as _, SubmittableExtrinsic,
+};
+
+use crate::{
+    block::split_extrinsic,
+    cli::{
+        AssetsCall, BlockId, DeipCall, DeipDaoCall, DeipDaoStorage, DeipFNFTCall, DeipFNFTStorage,
+        DeipProposalCall, DeipProposalStorage, DeipStorage, DeipVestingCall, Endpoints,
+        RuntimeVersion, Suri, WaitFor,
+    },
+    error::{Error, ErrorKind},
+    migration::PreMigration,
+    output,
+};
+
+pub type Signer = PairSigner<DefaultConfig, Pair>;
+pub type ExtrinsicParams = PolkadotExtrinsicParams<DefaultConfig>;
+pub type Header = <DefaultConfig as Config>::Header;
+
+/// `pallet_assets::Asset` entry, identical in both runtimes.
+#[derive(Debug, Serialize)]
+pub struct AssetInfo {
+    pub owner: AccountId32,
+    pub issuer: AccountId32,
+    pub admin: AccountId32,
+    pub freezer: AccountId32,
+    pub supply: u128,
+    pub deposit: u128,
+    pub min_balance: u128,
+    pub is_sufficient: bool,
+    pub accounts: u32,
+    pub sufficients: u32,
+    pub approvals: u32,
+    pub is_frozen: bool,
+}
+
+/// `DeipVesting::VestingPlans` entry, identical in both runtimes. Times are
+/// Timestamp pallet milliseconds.
+#[derive(Debug, Serialize)]
+pub struct VestingPlan {
+    pub start_time: u64,
+    pub cliff_duration: u64,
+    pub total_duration: u64,
+    pub interval: u64,
+    pub initial_amount: u128,
+    pub total_amount: u128,
+    pub vesting_during_cliff: bool,
+}
+
+/// `Balances::Locks` identifier of the balance DeipVesting keeps locked.
+pub const VESTING_LOCK_ID: [u8; 8] = *b"vesting ";
+
+/// Outcome of a submitted extrinsic.
+#[derive(Serialize)]
+pub struct TxResult {
+    pub extrinsic_hash: H256,
+    /// Not known with `--wait none`.
+    pub block_hash: Option<H256>,
+    pub block_number: Option<u32>,
+    /// The signer's `Balances::Withdraw`, net of the `Balances::Deposit`
+    /// refunding the fee of unused weight.
+    pub fee: u128,
+    pub events: Vec<EventRecord>,
+}
+
+#[derive(Serialize)]
+pub struct EventRecord {
+    /// Position of the event in the block.
+    pub index: u32,
+    /// `Pallet::Variant`.
+    pub name: String,
+    /// Event fields, keyed by name when the metadata names them.
+    pub fields: Value,
+    /// Text output line.
+    #[serde(skip)]
+    pub details: String,
+}
+
+impl EventRecord {
+    /// Describes `event`, with its fields decoded from `metadata`.
+    pub fn new<R: DeipRuntime>(
+        app: &R,
+        metadata: &Metadata,
+        event: EventDetails<R::Event>,
+    ) -> Self {
+        Self {
+            index: event.index,
+            name: format!("{}::{}", event.pallet, event.variant),
+            fields: fields::event_fields(metadata, &event.event),
+            details: app.describe_event(metadata, event),
+        }
+    }
+}
+
+/// `Pallet::Variant` names of the events emitted by submitted extrinsics.
+#[derive(Default)]
+pub struct EventLog(Mutex<Vec<String>>);
+
+impl EventLog {
+    pub fn push(&self, event: String) {
+        self.0.lock().unwrap().push(event);
+    }
+
+    /// Events logged since the previous call.
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+
+    /// Number of events logged since the last `take`.
+    pub fn count(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// Whether `event` was logged after the first `start` events.
+    pub fn logged_since(&self, start: usize, event: &str) -> bool {
+        let events = self.0.lock().unwrap();
+        events.iter().skip(start).any(|logged| logged == event)
+    }
+}
+
+/// Encoded calls of the extrinsics signed while recording, kept instead of
+/// being submitted so they can be batched into a proposal.
+#[derive(Default)]
+pub struct CallRecorder(Mutex<Option<Vec<(AccountId32, Vec<u8>)>>>);
+
+impl CallRecorder {
+    pub fn start(&self) {
+        *self.0.lock().unwrap() = Some(Vec::new());
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.0.lock().unwrap().is_some()
+    }
+
+    fn push(&self, signer: AccountId32, call: Vec<u8>) {
+        if let Some(calls) = self.0.lock().unwrap().as_mut() {
+            calls.push((signer, call));
+        }
+    }
+
+    /// Stops recording, returning the signer and call of each extrinsic.
+    pub fn finish(&self) -> Vec<(AccountId32, Vec<u8>)> {
+        self.0.lock().unwrap().take().unwrap_or_default()
+    }
+}
+
+/// Operations implemented by every generated runtime API, so commands
+/// are written once against this trait.
+pub trait DeipRuntime: Sized {
+    const NAME: &'static str;
+    /// Runtime spec version the API was generated from.
+    const SPEC_VERSION: u32;
+
+    type Event: Decode + Encode + Debug + Send + 'static;
+    type DispatchError: Decode + HasModuleError + Send + Sync + 'static;
+    type Call: Decode + Debug + Send;
+
+    fn new(client: Client<DefaultConfig>, wait: WaitFor) -> Self;
+
+    fn client(&self) -> &Client<DefaultConfig>;
+
+    fn event_log(&self) -> &EventLog;
+
+    /// How far `submit` follows an extrinsic.
+    fn wait(&self) -> WaitFor;
+
+    fn call_recorder(&self) -> &CallRecorder;
+
+    fn signer(&self, suri: Suri) -> Signer {
+        PairSigner::new(suri.0)
+    }
+
+    async fn asset(&self, id: u32) -> Result<Option<AssetInfo>>;
+
+    /// All `Assets::Asset` entries, ordered by id.
+    async fn assets(&self) -> Result<Vec<(u32, AssetInfo)>>;
+
+    /// `Timestamp::Now` of the best block.
+    async fn timestamp(&self) -> Result<u64>;
+
+    async fn vesting_plan(&self, account: &AccountId32) -> Result<Option<VestingPlan>>;
+
+    /// Balance of `account` the DeipVesting lock still holds, none once the
+    /// plan is fully unlocked.
+    async fn vesting_lock(&self, account: &AccountId32) -> Result<u128>;
+
+    async fn subscribe_events(
+        &self,
+    ) -> Result<EventSubscription<'_, EventSub<Header>, DefaultConfig, Self::Event>> {
+        Ok(subxt::events::subscribe::<DefaultConfig, Self::Event>(self.client()).await?)
+    }
+
+    async fn subscribe_finalized_events(
+        &self,
+    ) -> Result<EventSubscription<'_, FinalizedEventSub<'_, Header>, DefaultConfig, Self::Event>>
+    {
+        Ok(subxt::events::subscribe_finalized::<DefaultConfig, Self::Event>(self.client()).await?)
+    }
+
+    /// `System::set_code` wrapped into the runtime call enum.
+    fn set_code(code: Vec<u8>) -> Self::Call;
+
+    async fn sudo_unchecked_weight(&self, signer: Suri, call: Self::Call) -> Result<()>;
+
+    async fn deip_tx(&self, call: DeipCall) -> Result<()>;
+
+    async fn deip_storage(&self, storage: DeipStorage) -> Result<()>;
+
+    async fn deip_proposal_tx(&self, call: DeipProposalCall) -> Result<()>;
+
+    async fn deip_proposal_storage(&self, storage: DeipProposalStorage) -> Result<()>;
+
+    async fn deip_dao_tx(&self, call: DeipDaoCall) -> Result<()>;
+
+    async fn deip_dao_storage(&self, storage: DeipDaoStorage) -> Result<()>;
+
+    async fn deip_vesting_tx(&self, call: DeipVestingCall) -> Result<()>;
+
+    async fn assets_tx(&self, _call: AssetsCall) -> Result<()> {
+        bail!("Assets calls are not exposed by the {} runtime", Self::NAME)
+    }
+
+    async fn deip_fnft_tx(&self, _call: DeipFNFTCall) -> Result<()> {
+        bail!(
+            "DeipFNFT calls are not exposed by the {} runtime",
+            Self::NAME
+        )
+    }
+
+    async fn deip_fnft_storage(&self, _storage: DeipFNFTStorage) -> Result<()> {
+        bail!(
+            "DeipFNFT storage is not exposed by the {} runtime",
+            Self::NAME
+        )
+    }
+
+    /// Reads the `DeipAssets`/`DeipUniques` state the upgrade migrates.
+    async fn capture_migration(&self) -> Result<PreMigration> {
+        bail!("The {} runtime has nothing to migrate", Self::NAME)
+    }
+
+    /// Checks the migrated storage against `pre`, one line per mismatch.
+    async fn verify_migration(&self, _pre: &PreMigration) -> Result<Vec<String>> {
+        bail!("The {} runtime has no DeipFNFT storage", Self::NAME)
+    }
+
+    /// One line rendering of an event, starting with its `Pallet::Variant`,
+    /// with module errors decoded and documented from `metadata`.
+    fn describe_event(&self, metadata: &Metadata, event: EventDetails<Self::Event>) -> String;
+
+    /// Decoded dispatch error, if `event` reports a failed call.
+    fn dispatch_failure(&self, event: &Self::Event) -> Option<String>;
+
+    /// Change `event` makes to the fee `payer` paid for an extrinsic: the fee
+    /// withdrawal, or the refund of its unused weight as a negative amount.
+    fn fee_paid(event: &Self::Event, payer: &AccountId32) -> Option<i128>;
+
+    /// Signs `tx` with `signer`, follows it as far as `wait` says and outputs
+    /// its result. While recording, only keeps its encoded call.
+    async fn submit<C>(
+        &self,
+        tx: SubmittableExtrinsic<
+            '_,
+            DefaultConfig,
+            ExtrinsicParams,
+            C,
+            Self::DispatchError,
+            Self::Event,
+        >,
+        signer: Suri,
+    ) -> Result<()>
+    where
+        C: subxt::Call + Send + Sync,
+    {
+        let signer = self.signer(signer);
+        if self.call_recorder().is_recording() {
+            let extrinsic = tx.create_signed(&signer, Default::default()).await?;
+            let (_, call) = split_extrinsic(&extrinsic.0)?;
+            self.call_recorder()
+                .push(signer.account_id().clone(), call.to_vec());
+            return Ok(());
+        }
+        let progress = match self.wait() {
+            WaitFor::None => {
+                let extrinsic_hash = tx
+                    .sign_and_submit_default(&signer)
+                    .await
+                    .map_err(ErrorKind::Rejected.wrap())?;
+                let result = TxResult {
+                    extrinsic_hash,
+                    block_hash: None,
+                    block_number: None,
+                    fee: 0,
+                    events: Vec::new(),
+                };
+                return output::emit(&result, |result| {
+                    info!("Extrinsic {:?} submitted", result.extrinsic_hash)
+                });
+            }
+            _ => tx
+                .sign_and_submit_then_watch_default(&signer)
+                .await
+                .map_err(ErrorKind::Rejected.wrap())?,
+        };
+        // Failed dispatches are reported from the events below, as
+        // `wait_for_finalized_success` would, but after displaying them.
+        let in_block = match self.wait() {
+            WaitFor::Finalized => progress.wait_for_finalized().await,
+            _ => progress.wait_for_in_block().await,
+        }
+        .map_err(ErrorKind::Rejected.wrap())?;
+        let block_hash = in_block.block_hash();
+        let header = self
+            .client()
+            .rpc()
+            .header(Some(block_hash))
+            .await
+            .map_err(ErrorKind::Connection.wrap())?;
+        let mut result = TxResult {
+            extrinsic_hash: in_block.extrinsic_hash(),
+            block_hash: Some(block_hash),
+            block_number: header.map(|header| header.number),
+            fee: 0,
+            events: Vec::new(),
+        };
+        let events = in_block
+            .fetch_events()
+            .await
+            .map_err(ErrorKind::Decoding.wrap())?;
+        let mut failures = Vec::new();
+        for event in events.iter() {
+            let event = event.map_err(ErrorKind::Decoding.wrap())?;
+            let fee = Self::fee_paid(&event.event, signer.account_id()).unwrap_or(0);
+            result.fee = result.fee.saturating_add_signed(fee);
+            failures.extend(self.dispatch_failure(&event.event));
+            let record = EventRecord::new(self, &self.client().metadata().read(), event);
+            self.event_log().push(record.name.clone());
+            result.events.push(record);
+        }
+        output::emit(&result, |result| {
+            let block_number = result.block_number.map(|number| format!("#{number} "));
+            info!(
+                "Extrinsic {:?} in block {}{:?}, fee {}",
+                result.extrinsic_hash,
+                block_number.unwrap_or_default(),
+                block_hash,
+                result.fee,
+            );
+            for event in &result.events {
+                info!(" - {}", event.details);
+            }
+        })?;
+        if !failures.is_empty() {
+            let message = failures.join("; ");
+            return Err(Error {
+                kind: ErrorKind::Dispatch,
+                message,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Resolves `at` to a block hash, the best block if not given.
+pub async fn block_hash(client: &Client<DefaultConfig>, at: Option<BlockId>) -> Result<H256> {
+    let number = match at {
+        Some(BlockId::Hash(hash)) => return Ok(hash),
+        Some(BlockId::Number(number)) => Some(number.into()),
+        None => None,
+    };
+    client
+        .rpc()
+        .block_hash(number)
+        .await?
+        .ok_or_else(|| anyhow!("Block {at:?} not found"))
+}
+
+/// Length of the Twox128 pallet and entry prefixes of storage keys.
+const STORAGE_PREFIX_LEN: usize = 32;
+
+/// Hash lengths of the keys of a map, to decode the keys back out of its
+/// storage keys.
+pub struct MapKeys(Vec<usize>);
+
+/// Keys of one storage key, decoded in declaration order.
+pub struct KeyReader<'a, 'k> {
+    hash_lens: std::slice::Iter<'a, usize>,
+    key: &'k [u8],
+}
+
+impl MapKeys {
+    pub fn new(
+        client: &Client<DefaultConfig>,
+        pallet: &'static str,
+        entry: &'static str,
+    ) -> Result<Self> {
+        let metadata = client.metadata();
+        let metadata = metadata.read();
+        let hashers = match &metadata.pallet(pallet)?.storage(entry)?.ty {
+            StorageEntryType::Map { hashers, .. } => hashers,
+            _ => bail!("{pallet}::{entry} is not a map"),
+        };
+        let hash_lens = hashers
+            .iter()
+            .map(|hasher| match hasher {
+                StorageHasher::Identity => Ok(0),
+                StorageHasher::Twox64Concat => Ok(8),
+                StorageHasher::Blake2_128Concat => Ok(16),
+                hasher => {
+                    bail!("{pallet}::{entry} keys are not recoverable from {hasher:?} hashes")
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self(hash_lens))
+    }
+
+    pub fn read<'k>(&self, storage_key: &'k [u8]) -> KeyReader<'_, 'k> {
+        KeyReader {
+            hash_lens: self.0.iter(),
+            key: storage_key.get(STORAGE_PREFIX_LEN..).unwrap_or_default(),
+        }
+    }
+}
+
+impl KeyReader<'_, '_> {
+    /// Decodes the next key, skipping its hash.
+    pub fn next_key<K: Decode>(&mut self) -> Result<K> {
+        let hash_len = self
+            .hash_lens
+            .next()
+            .ok_or_else(|| anyhow!("Storage key has no more keys"))?;
+        self.key = self
+            .key
+            .get(*hash_len..)
+            .ok_or_else(|| anyhow!("Storage key is too short"))?;
+        Ok(K::decode(&mut self.key)?)
+    }
+}
+
+/// Whether `value` passes an optional key filter.
+pub fn key_matches<T: PartialEq>(filter: &Option<T>, value: &T) -> bool {
+    filter.as_ref().is_none_or(|filter| filter == value)
+}
+
+/// Renders a module error as `Pallet::Error: docs`, named and documented
+/// from the metadata of the runtime that raised it.
+pub fn describe_module_error(metadata: &Metadata, index: u8, error: u8) -> String {
+    match metadata.error(index, error) {
+        Ok(details) => {
+            let docs: Vec<_> = details
+                .description()
+                .iter()
+                .map(|line| line.trim())
+                .collect();
+            format!(
+                "{}::{}: {}",
+                details.pallet(),
+                details.error(),
+                docs.join(" ")
+            )
+        }
+        Err(_) => format!("Module({index})::UnknownError({error})"),
+    }
+}
+
+/// Metadata of the runtime at block `at`, which differs from the client's
+/// for blocks executed before an upgrade.
+pub async fn metadata_at(client: &Client<DefaultConfig>, at: H256) -> Result<Metadata> {
+    let bytes: Bytes = client
+        .rpc()
+        .client
+        .request("state_getMetadata", rpc_params![at])
+        .await
+        .map_err(ErrorKind::Connection.wrap())?;
+    let metadata =
+        RuntimeMetadataPrefixed::decode(&mut &bytes[..]).map_err(ErrorKind::Decoding.wrap())?;
+    Ok(Metadata::try_from(metadata).map_err(ErrorKind::Decoding.wrap())?)
+}
+
+async fn connect_client(url: &str) -> Result<(Client<DefaultConfig>, u32)> {
+    let client = ClientBuilder::new()
+        .set_url(url)
+        .build()
+        .await
+        .map_err(ErrorKind::Connection.wrap())?;
+    let spec_version = client
+        .rpc()
+        .runtime_version(None)
+        .await
+        .map_err(ErrorKind::Connection.wrap())?
+        .spec_version;
+    info!("Connected to {url}, spec_version: {spec_version}");
+    Ok((client, spec_version))
+}
+
+fn unsupported_spec_version(spec_version: u32) -> anyhow::Error {
+    anyhow!(
+        "Node runs spec_version {spec_version}, supported: {} ({}), {} ({}); \
+         use --force-runtime to override",
+        master::App::SPEC_VERSION,
+        master::App::NAME,
+        develop::App::SPEC_VERSION,
+        develop::App::NAME,
+    )
+}
+
+impl RuntimeVersion {
+    /// The runtime generated from `spec_version`, if any.
+    pub fn for_spec_version(spec_version: u32) -> Option<Self> {
+        match spec_version {
+            master::App::SPEC_VERSION => Some(Self::Master),
+            develop::App::SPEC_VERSION => Some(Self::Develop),
+            _ => None,
+        }
+    }
+
+    /// `DeipRuntime::NAME` of the runtime.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Master => master::App::NAME,
+            Self::Develop => develop::App::NAME,
+        }
+    }
+}
+
+/// Client bound to the generated API matching the node's runtime.
+pub enum Runtime {
+    Master(master::App),
+    Develop(develop::App),
+}
+
+impl Runtime {
+    /// Picks the API by the `spec_version` of the node at the first of
+    /// `endpoints.probe_urls()` answering, unless `force` overrides it, and
+    /// connects to the endpoint of that runtime.
+    pub async fn connect(
+        endpoints: &Endpoints,
+        force: Option<RuntimeVersion>,
+        wait: WaitFor,
+    ) -> Result<Self> {
+        Ok(Self::connect_with_url(endpoints, force, wait).await?.0)
+    }
+
+    /// `connect`, also returning the url of the node connected to.
+    pub async fn connect_with_url(
+        endpoints: &Endpoints,
+        force: Option<RuntimeVersion>,
+        wait: WaitFor,
+    ) -> Result<(Self, String)> {
+        if let Some(version) = force {
+            warn!("Runtime forced to {version:?}");
+            let url = endpoints
+                .url(version)
+                .unwrap_or_else(|| endpoints.probe_urls()[0]);
+            let (client, _) = connect_client(url).await?;
+            return Ok((Self::new(client, version, wait), url.to_owned()));
+        }
+        let mut probed = Err(anyhow!("No node endpoint to probe"));
+        for url in endpoints.probe_urls() {
+            probed = connect_client(url).await.map(|connected| (connected, url));
+            match &probed {
+                Ok(_) => break,
+                Err(err) => warn!("{url}: {err:#}"),
+            }
+        }
+        let ((mut client, mut spec_version), mut url) = probed?;
+        let version = RuntimeVersion::for_spec_version(spec_version);
+        if let Some(runtime_url) = version.and_then(|version| endpoints.url(version)) {
+            if runtime_url != url {
+                (client, spec_version) = connect_client(runtime_url).await?;
+                url = runtime_url;
+            }
+        }
+        let runtime = Self::for_spec_version(client, spec_version, wait)
+            .ok_or_else(|| unsupported_spec_version(spec_version))?;
+        Ok((runtime, url.to_owned()))
+    }
+
+    /// Connects to the node at `url`, with the API picked by its
+    /// `spec_version`.
+    pub async fn connect_url(url: &str, wait: WaitFor) -> Result<Self> {
+        let (client, spec_version) = connect_client(url).await?;
+        Self::for_spec_version(client, spec_version, wait)
+            .ok_or_else(|| unsupported_spec_version(spec_version))
+    }
+
+    pub fn new(client: Client<DefaultConfig>, version: RuntimeVersion, wait: WaitFor) -> Self {
+        match version {
+            RuntimeVersion::Master => Self::Master(master::App::new(client, wait)),
+            RuntimeVersion::Develop => Self::Develop(develop::App::new(client, wait)),
+        }
+    }
+
+    /// The API generated from `spec_version`, if any.
+    pub fn for_spec_version(
+        client: Client<DefaultConfig>,
+        spec_version: u32,
+        wait: WaitFor,
+    ) -> Option<Self> {
+        let version = RuntimeVersion::for_spec_version(spec_version)?;
+        Some(Self::new(client, version, wait))
+    }
+
+    pub fn client(&self) -> &Client<DefaultConfig> {
+        match self {
+            Self::Master(app) => app.client(),
+            Self::Develop(app) => app.client(),
+        }
+    }
+
+    pub fn spec_version(&self) -> u32 {
+        match self {
+            Self::Master(_) => master::App::SPEC_VERSION,
+            Self::Develop(_) => develop::App::SPEC_VERSION,
+        }
+    }
+
+    pub fn event_log(&self) -> &EventLog {
+        match self {
+            Self::Master(app) => app.event_log(),
+            Self::Develop(app) => app.event_log(),
+        }
+    }
+}
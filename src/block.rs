@@ -0,0 +1,272 @@
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use parity_scale_codec::{Compact, Decode, Encode};
+use serde::Serialize;
+use subxt::{
+    events::Phase,
+    sp_core::{hashing::twox_128, storage::StorageKey, H256},
+    sp_runtime::{generic::Era, MultiAddress, MultiSignature},
+    Config, DefaultConfig, EventDetails, Metadata,
+};
+
+use crate::{
+    app::{block_hash, metadata_at, DeipRuntime, EventRecord, Header, Runtime},
+    cli::BlockId,
+    output,
+};
+
+/// Signed extrinsics of the supported transaction version.
+const EXTRINSIC_VERSION: u8 = 4;
+
+#[derive(Serialize)]
+struct BlockReport {
+    hash: H256,
+    number: u32,
+    parent_hash: H256,
+    state_root: H256,
+    extrinsics_root: H256,
+    /// Version of the runtime that executed the block.
+    spec_version: u32,
+    runtime: &'static str,
+    initialization: Vec<EventRecord>,
+    extrinsics: Vec<ExtrinsicReport>,
+    finalization: Vec<EventRecord>,
+}
+
+#[derive(Serialize)]
+struct ExtrinsicReport {
+    index: u32,
+    /// SS58 address, unsigned extrinsics have none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tip: Option<u128>,
+    call: String,
+    events: Vec<EventRecord>,
+}
+
+/// Outputs block `at`, decoded with the API generated from the runtime of its
+/// parent's state and with that state's metadata, so that the block enacting
+/// an upgrade still decodes with the runtime being replaced. Unknown spec
+/// versions fall back to `app`.
+pub async fn show<R: DeipRuntime>(app: &R, at: BlockId) -> Result<()> {
+    let hash = block_hash(app.client(), Some(at)).await?;
+    let header = app
+        .client()
+        .rpc()
+        .header(Some(hash))
+        .await?
+        .ok_or_else(|| anyhow!("Block {at:?} not found"))?;
+    let state = match header.number {
+        0 => hash,
+        _ => header.parent_hash,
+    };
+    let spec_version = app
+        .client()
+        .rpc()
+        .runtime_version(Some(state))
+        .await?
+        .spec_version;
+    let metadata = metadata_at(app.client(), state).await?;
+
+    let report = if spec_version == R::SPEC_VERSION {
+        decode_block(app, &metadata, hash, header, spec_version).await?
+    } else {
+        match Runtime::for_spec_version(app.client().clone(), spec_version, app.wait()) {
+            Some(Runtime::Master(app)) => {
+                decode_block(&app, &metadata, hash, header, spec_version).await?
+            }
+            Some(Runtime::Develop(app)) => {
+                decode_block(&app, &metadata, hash, header, spec_version).await?
+            }
+            None => {
+                warn!(
+                    "Block executed by spec_version {spec_version}, decoding with {}",
+                    R::NAME
+                );
+                decode_block(app, &metadata, hash, header, spec_version).await?
+            }
+        }
+    };
+    output::emit(&report, display_block)
+}
+
+async fn decode_block<R: DeipRuntime>(
+    app: &R,
+    metadata: &Metadata,
+    hash: H256,
+    header: Header,
+    spec_version: u32,
+) -> Result<BlockReport> {
+    let block = app
+        .client()
+        .rpc()
+        .block(Some(hash))
+        .await?
+        .ok_or_else(|| anyhow!("Block {hash:?} not found"))?;
+    let mut report = BlockReport {
+        hash,
+        number: header.number,
+        parent_hash: header.parent_hash,
+        state_root: header.state_root,
+        extrinsics_root: header.extrinsics_root,
+        spec_version,
+        runtime: R::NAME,
+        initialization: Vec::new(),
+        extrinsics: Vec::new(),
+        finalization: Vec::new(),
+    };
+    for (index, extrinsic) in block.block.extrinsics.iter().enumerate() {
+        let extrinsic = decode_extrinsic::<R>(index as u32, &extrinsic.encode())?;
+        report.extrinsics.push(extrinsic);
+    }
+
+    for (phase, record) in block_events(app, metadata, hash).await? {
+        let events = match phase {
+            Phase::Initialization => &mut report.initialization,
+            Phase::Finalization => &mut report.finalization,
+            Phase::ApplyExtrinsic(index) => {
+                &mut report
+                    .extrinsics
+                    .get_mut(index as usize)
+                    .ok_or_else(|| anyhow!("Event {} of missing extrinsic {index}", record.index))?
+                    .events
+            }
+        };
+        events.push(record);
+    }
+    Ok(report)
+}
+
+/// Events of block `hash`, read from `System::Events` rather than through the
+/// client, whose metadata may not be the one the block was executed with.
+async fn block_events<R: DeipRuntime>(
+    app: &R,
+    metadata: &Metadata,
+    hash: H256,
+) -> Result<Vec<(Phase, EventRecord)>> {
+    let mut key = twox_128(b"System").to_vec();
+    key.extend(twox_128(b"Events"));
+    let bytes = match app
+        .client()
+        .rpc()
+        .storage(&StorageKey(key), Some(hash))
+        .await?
+    {
+        Some(bytes) => bytes.0,
+        None => return Ok(Vec::new()),
+    };
+    let input = &mut &bytes[..];
+    let Compact(count) = Compact::<u32>::decode(input)?;
+    let mut events = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let phase = Phase::decode(input)?;
+        let event = R::Event::decode(input)?;
+        let _topics = Vec::<H256>::decode(input)?;
+        let (pallet_index, variant_index) = match event.encode()[..] {
+            [pallet_index, variant_index, ..] => (pallet_index, variant_index),
+            _ => bail!("Event {index} has no variant"),
+        };
+        let event_metadata = metadata.event(pallet_index, variant_index)?;
+        let event = EventDetails {
+            phase: phase.clone(),
+            index,
+            pallet: event_metadata.pallet().to_string(),
+            variant: event_metadata.event().to_string(),
+            event,
+        };
+        events.push((phase, EventRecord::new(app, metadata, event)));
+    }
+    Ok(events)
+}
+
+/// Signer, nonce and tip of a signed extrinsic.
+pub struct SignedPart {
+    pub address: <DefaultConfig as Config>::Address,
+    pub nonce: u32,
+    pub tip: u128,
+}
+
+/// Splits an encoded extrinsic into its signed part and its encoded call, in
+/// the layout of `ExtrinsicParams`: the signed extensions carry the era, nonce
+/// and tip, in that order.
+pub fn split_extrinsic(mut bytes: &[u8]) -> Result<(Option<SignedPart>, &[u8])> {
+    let input = &mut bytes;
+    let Compact(_len) = Compact::<u32>::decode(input)?;
+    let version = u8::decode(input)?;
+    ensure!(
+        version & 0x7f == EXTRINSIC_VERSION,
+        "Unsupported extrinsic version {}",
+        version & 0x7f
+    );
+    let signed = if version & 0x80 != 0 {
+        let address = <DefaultConfig as Config>::Address::decode(input)?;
+        let _signature = MultiSignature::decode(input)?;
+        let _era = Era::decode(input)?;
+        let Compact(nonce) = Compact::<u32>::decode(input)?;
+        let Compact(tip) = Compact::<u128>::decode(input)?;
+        Some(SignedPart {
+            address,
+            nonce,
+            tip,
+        })
+    } else {
+        None
+    };
+    Ok((signed, bytes))
+}
+
+fn decode_extrinsic<R: DeipRuntime>(index: u32, bytes: &[u8]) -> Result<ExtrinsicReport> {
+    let (signed, mut call) =
+        split_extrinsic(bytes).with_context(|| format!("Extrinsic {index}"))?;
+    let signer = signed.as_ref().map(|signed| match &signed.address {
+        MultiAddress::Id(account) => account.to_string(),
+        address => format!("{address:?}"),
+    });
+    Ok(ExtrinsicReport {
+        index,
+        signer,
+        nonce: signed.as_ref().map(|signed| signed.nonce),
+        tip: signed.as_ref().map(|signed| signed.tip),
+        call: format!("{:?}", R::Call::decode(&mut call)?),
+        events: Vec::new(),
+    })
+}
+
+fn display_block(report: &BlockReport) {
+    info!(
+        "Block #{} {:?}, spec_version {} ({})",
+        report.number, report.hash, report.spec_version, report.runtime
+    );
+    info!(" parent {:?}", report.parent_hash);
+    info!(" state root {:?}", report.state_root);
+    info!(" extrinsics root {:?}", report.extrinsics_root);
+    display_events("Initialization", &report.initialization);
+    for extrinsic in &report.extrinsics {
+        match (&extrinsic.signer, extrinsic.nonce, extrinsic.tip) {
+            (Some(signer), Some(nonce), Some(tip)) => info!(
+                "Extrinsic {} by {signer}, nonce {nonce}, tip {tip}: {}",
+                extrinsic.index, extrinsic.call
+            ),
+            _ => info!(
+                "Extrinsic {} (unsigned): {}",
+                extrinsic.index, extrinsic.call
+            ),
+        }
+        for event in &extrinsic.events {
+            info!(" - {}", event.details);
+        }
+    }
+    display_events("Finalization", &report.finalization);
+}
+
+fn display_events(phase: &str, events: &[EventRecord]) {
+    if events.is_empty() {
+        return;
+    }
+    info!("{phase}:");
+    for event in events {
+        info!(" - {}", event.details);
+    }
+}
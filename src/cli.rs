@@ -1,22 +1,129 @@
 use clap::{Parser, Subcommand, ValueEnum};
-use sp_keyring::AccountKeyring;
+use sp_keyring::{sr25519::sr25519::Pair, AccountKeyring};
+use std::{path::PathBuf, str::FromStr, time::Duration};
+use subxt::{
+    sp_core::{crypto::Ss58Codec, Pair as _, H160, H256},
+    sp_runtime::{AccountId32, MultiAddress},
+};
+
+use crate::{
+    config::{Interval, Pacing},
+    error::EXIT_CODES,
+};
 
 #[derive(Parser)]
+#[clap(after_help = EXIT_CODES)]
 pub struct Args {
+    #[clap(flatten)]
+    pub endpoints: Endpoints,
+    /// Use this runtime's API even if the node reports another spec version.
+    #[clap(long, global = true, value_enum)]
+    pub force_runtime: Option<RuntimeVersion>,
+    /// Pause after each step: none, timer, input (enter key) or finalized.
+    #[clap(long, global = true, default_value = "none", value_parser)]
+    pub interval: Interval,
+    /// Seconds to sleep with `--interval timer`.
+    #[clap(long, global = true, default_value_t = 6, value_parser)]
+    pub interval_duration: u64,
+    /// Finalized blocks to wait for with `--interval finalized`.
+    #[clap(long, global = true, default_value_t = 1, value_parser)]
+    pub interval_blocks: u32,
+    /// How long to follow submitted extrinsics.
+    #[clap(long, global = true, default_value = "in-block", value_enum)]
+    pub wait: WaitFor,
+    /// Results as log lines on stderr, or as JSON lines on stdout.
+    #[clap(
+        long = "output",
+        id = "format",
+        global = true,
+        default_value = "text",
+        value_enum
+    )]
+    pub format: OutputFormat,
     #[clap(subcommand)]
-    pub runtime_version: RuntimeVersion,
+    pub endpoint: EndPoint,
 }
 
-#[derive(Subcommand)]
+impl Args {
+    pub fn pacing(&self) -> Pacing {
+        Pacing {
+            interval: self.interval,
+            duration: Duration::from_secs(self.interval_duration),
+            blocks: self.interval_blocks,
+        }
+    }
+}
+
+/// Node endpoint used when none is given.
+const DEFAULT_URL: &str = "ws://127.0.0.1:9944";
+
+/// Node endpoints. The runtime is detected through `url`, or without it
+/// through the first runtime-specific url answering, then commands go to the
+/// endpoint given for that runtime, if any.
+#[derive(clap::Args, Clone)]
+pub struct Endpoints {
+    /// Node websocket endpoint used when no runtime-specific url is given,
+    /// ws://127.0.0.1:9944 if no url is given at all.
+    #[clap(long, global = true, env = "DEIP_NODE_URL", value_parser)]
+    pub url: Option<String>,
+    /// Node websocket endpoint for the master runtime, overrides `--url`.
+    #[clap(long, global = true, env = "DEIP_MASTER_URL", value_parser)]
+    pub master_url: Option<String>,
+    /// Node websocket endpoint for the develop runtime, overrides `--url`.
+    #[clap(long, global = true, env = "DEIP_DEVELOP_URL", value_parser)]
+    pub develop_url: Option<String>,
+}
+
+impl Endpoints {
+    /// Endpoint of `version`, `url` unless a runtime-specific one is given.
+    pub fn url(&self, version: RuntimeVersion) -> Option<&str> {
+        let url = match version {
+            RuntimeVersion::Master => &self.master_url,
+            RuntimeVersion::Develop => &self.develop_url,
+        };
+        url.as_deref().or(self.url.as_deref())
+    }
+
+    /// Endpoints to detect the runtime through, in order.
+    pub fn probe_urls(&self) -> Vec<&str> {
+        if let Some(url) = self.url.as_deref() {
+            return vec![url];
+        }
+        let urls: Vec<_> = [&self.master_url, &self.develop_url]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        if urls.is_empty() {
+            vec![DEFAULT_URL]
+        } else {
+            urls
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
 pub enum RuntimeVersion {
-    Master {
-        #[clap(subcommand)]
-        endpoint: EndPoint,
-    },
-    Develop {
-        #[clap(subcommand)]
-        endpoint: EndPoint,
-    },
+    /// `master_v104`, spec version 104.
+    Master,
+    /// `develop_v105`, spec version 105.
+    Develop,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum WaitFor {
+    /// Until included in a best block, which may still be reorged out.
+    InBlock,
+    /// Until the including block is finalized.
+    Finalized,
+    /// Only until accepted by the transaction pool.
+    None,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -29,17 +136,200 @@ pub enum EndPoint {
         #[clap(subcommand)]
         pallet: PalletStorage,
     },
+    /// Dumps raw storage of the given pallets into a json file.
+    Snapshot {
+        /// JSON file the snapshot is written to.
+        #[clap(short, long = "out", value_parser)]
+        output: PathBuf,
+        /// Block number or hash, the best block by default.
+        #[clap(long, value_parser)]
+        at: Option<BlockId>,
+        #[clap(
+            short,
+            long,
+            value_parser,
+            use_value_delimiter = true,
+            default_value = "Assets,Uniques,DeipAssets,DeipUniques,DeipFNFT,DeipInvestmentOpportunity"
+        )]
+        pallets: Vec<String>,
+    },
+    /// Compares two snapshots, e.g. taken before and after a runtime upgrade.
+    Diff {
+        #[clap(value_parser)]
+        pre: PathBuf,
+        #[clap(value_parser)]
+        post: PathBuf,
+    },
+    /// Checks the DeipAssets/DeipUniques to DeipFNFT storage migration.
+    VerifyMigration {
+        #[clap(subcommand)]
+        step: MigrationStep,
+    },
+    /// Runs the steps of a `.toml` or `.yaml` scenario over one connection.
+    Run {
+        #[clap(value_parser)]
+        scenario: PathBuf,
+    },
+    Events {
+        #[clap(subcommand)]
+        command: EventsCommand,
+    },
+    Block {
+        #[clap(subcommand)]
+        command: BlockCommand,
+    },
+    Vesting {
+        #[clap(subcommand)]
+        command: VestingCommand,
+    },
+}
+
+/// Command of a scenario step, parsed like the command line without global options.
+#[derive(Parser)]
+#[clap(no_binary_name = true)]
+pub struct StepCommand {
+    #[clap(subcommand)]
+    pub endpoint: EndPoint,
+}
+
+#[derive(Subcommand)]
+pub enum EventsCommand {
+    /// Prints the events of every new block until interrupted, reconnecting
+    /// when the connection drops or the runtime is upgraded.
+    Watch {
+        /// Follow finalized blocks instead of best blocks.
+        #[clap(long, action)]
+        finalized: bool,
+        #[clap(flatten)]
+        filter: EventFilter,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BlockCommand {
+    /// Prints the header, extrinsics and events of a block, decoded with the
+    /// runtime that executed it.
+    Show {
+        /// Block number or hash.
+        #[clap(value_parser)]
+        at: BlockId,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VestingCommand {
+    /// Prints the `DeipVesting` plan of an account with its unlockable
+    /// amount and upcoming releases.
+    Show {
+        #[clap(value_parser)]
+        account: Account,
+        /// Upcoming releases to print.
+        #[clap(short = 'n', long, value_parser, default_value_t = 10)]
+        releases: usize,
+    },
+}
+
+/// Events to print, all of them by default.
+#[derive(clap::Args, Clone)]
+pub struct EventFilter {
+    /// Pallet names, e.g. `Assets,DeipFNFT`.
+    #[clap(short, long = "pallet", value_parser, use_value_delimiter = true)]
+    pub pallets: Vec<String>,
+    /// Event names, either `Variant` or `Pallet::Variant`.
+    #[clap(short, long = "event", value_parser, use_value_delimiter = true)]
+    pub events: Vec<String>,
+    /// Only events mentioning this account.
+    #[clap(short, long, value_parser)]
+    pub account: Option<Account>,
+}
+
+#[derive(Subcommand)]
+pub enum MigrationStep {
+    /// Saves the master state to be migrated, run before the upgrade.
+    Capture {
+        /// JSON file the captured state is written to.
+        #[clap(short, long = "out", value_parser)]
+        output: PathBuf,
+    },
+    /// Compares the captured state with develop storage, run after the upgrade.
+    Check {
+        #[clap(value_parser)]
+        input: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum BlockId {
+    Number(u32),
+    Hash(H256),
+}
+
+impl FromStr for BlockId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") {
+            parse_h256(s).map(Self::Hash)
+        } else {
+            s.parse().map(Self::Number).map_err(|err| format!("{err}"))
+        }
+    }
+}
+
+pub fn parse_h160(s: &str) -> Result<H160, String> {
+    parse_hex(s, 20).map(|bytes| H160::from_slice(&bytes))
+}
+
+pub fn parse_h256(s: &str) -> Result<H256, String> {
+    parse_hex(s, 32).map(|bytes| H256::from_slice(&bytes))
+}
+
+/// `0x` prefixed hex of exactly `len` bytes.
+fn parse_hex(s: &str, len: usize) -> Result<Vec<u8>, String> {
+    let bytes = parse_bytes(s)?;
+    if bytes.len() != len {
+        return Err(format!("expected {len} bytes, got {}", bytes.len()));
+    }
+    Ok(bytes)
+}
+
+/// `0x` prefixed hex of any length.
+pub fn parse_bytes(s: &str) -> Result<Vec<u8>, String> {
+    let hex = s
+        .strip_prefix("0x")
+        .ok_or("expected a 0x prefixed hex string")?;
+    hex::decode(hex).map_err(|err| err.to_string())
 }
 
 #[derive(Subcommand)]
 pub enum PalletCall {
     Assets {
         #[clap(subcommand)]
-        call: Call,
+        call: AssetsCall,
     },
     Sudo {
         #[clap(subcommand)]
-        call: Call,
+        call: SudoCall,
+    },
+    DeipFNFT {
+        #[clap(subcommand)]
+        call: DeipFNFTCall,
+    },
+    Deip {
+        #[clap(subcommand)]
+        call: DeipCall,
+    },
+    DeipProposal {
+        #[clap(subcommand)]
+        call: DeipProposalCall,
+    },
+    DeipDao {
+        #[clap(subcommand)]
+        call: DeipDaoCall,
+    },
+    DeipVesting {
+        #[clap(subcommand)]
+        call: DeipVestingCall,
     },
 }
 
@@ -53,22 +343,131 @@ pub enum PalletStorage {
         #[clap(subcommand)]
         storage: DeipFNFTStorage,
     },
+    Deip {
+        #[clap(subcommand)]
+        storage: DeipStorage,
+    },
+    DeipProposal {
+        #[clap(subcommand)]
+        storage: DeipProposalStorage,
+    },
+    DeipDao {
+        #[clap(subcommand)]
+        storage: DeipDaoStorage,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum AssetsStorage {
+    /// Details of one asset.
     Asset {
         #[clap(short, value_parser)]
         key: u32,
     },
+    /// Details of every asset.
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum DeipDaoStorage {
+    /// DAOs with their account and authority.
+    DaoRepository {
+        #[clap(short, long, value_parser = parse_h160)]
+        id: Option<H160>,
+    },
+    /// DAO ids by DAO account, resolved to the DAO when an account is given.
+    DaoLookup {
+        #[clap(short, long, value_parser)]
+        account: Option<Account>,
+    },
 }
 
 #[derive(Subcommand)]
-pub enum DeipFNFTStorage {}
+pub enum DeipProposalStorage {
+    ProposalRepository {
+        #[clap(short, long, value_parser = parse_h160)]
+        id: Option<H160>,
+    },
+}
+
+/// `pallet_deip` storage, every entry matching the given keys.
+#[derive(Subcommand)]
+pub enum DeipStorage {
+    ContractAgreementMap {
+        #[clap(short, long, value_parser = parse_h160)]
+        id: Option<H160>,
+    },
+    /// Agreement ids indexed by their terms.
+    ContractAgreementIdByType {
+        #[clap(short, long, value_enum)]
+        terms: Option<ContractTerms>,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: Option<H160>,
+    },
+}
+
+/// `pallet_deip_f_nft` storage. Maps print every entry matching the given
+/// keys, or the single entry when all of them are given.
+#[derive(Subcommand)]
+pub enum DeipFNFTStorage {
+    CollectionRepo {
+        #[clap(short, long, value_parser = parse_h160)]
+        collection: Option<H160>,
+    },
+    ItemRepo {
+        #[clap(short, long, value_parser = parse_h256)]
+        fingerprint: Option<H256>,
+    },
+    FractionRepo {
+        #[clap(short, long, value_parser = parse_h256)]
+        fingerprint: Option<H256>,
+        #[clap(short, long, value_parser)]
+        account: Option<Account>,
+    },
+    FractionalRepo {
+        #[clap(short, long, value_parser = parse_h256)]
+        fingerprint: Option<H256>,
+    },
+    FractionHolds {
+        #[clap(short, long, value_parser = parse_h256)]
+        fingerprint: Option<H256>,
+        #[clap(short, long, value_parser)]
+        account: Option<Account>,
+        #[clap(long, value_parser = parse_h160)]
+        hold_id: Option<H160>,
+        #[clap(long, value_parser)]
+        hold_index: Option<u32>,
+    },
+    NextCollectionId {
+        #[clap(flatten)]
+        check: Expectation,
+    },
+    #[clap(name = "next-ftoken-id", alias = "next_ftoken_id")]
+    NextFTokenId {
+        #[clap(flatten)]
+        check: Expectation,
+    },
+    FingerprintByFractionTokenId {
+        #[clap(short, long, value_parser)]
+        id: Option<u32>,
+    },
+}
+
+/// Assertion on a storage value, for scripts and scenarios.
+#[derive(clap::Args)]
+pub struct Expectation {
+    /// Fails unless the value equals this one.
+    #[clap(long, value_parser)]
+    pub expect: Option<u32>,
+}
 
+/// `pallet_assets` extrinsics, `force-*` ones are dispatched through `Sudo::sudo`.
 #[derive(Subcommand)]
-pub enum Call {
+pub enum AssetsCall {
+    /// The signer becomes the owner.
     Create {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
         #[clap(short, value_parser)]
         id: u32,
         #[clap(short, value_parser)]
@@ -76,21 +475,724 @@ pub enum Call {
         #[clap(short, value_parser)]
         min_balance: u128,
     },
+    /// Creates an asset without reserving a deposit.
+    ForceCreate {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        owner: Account,
+        #[clap(long, action)]
+        is_sufficient: bool,
+        #[clap(long, value_parser)]
+        min_balance: u128,
+    },
+    /// Destroys an asset, the witness is read from `Assets::Asset`.
+    Destroy {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+    },
+    /// Mints `amount` to `beneficiary`, signed by the issuer.
+    Mint {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        beneficiary: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+    /// Burns up to `amount` from `who`, signed by the admin.
+    Burn {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        who: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+    Transfer {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        target: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+    TransferKeepAlive {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        target: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+    ForceTransfer {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        source: Account,
+        #[clap(long, value_parser)]
+        dest: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+    /// Freezes `who` account, signed by the freezer.
+    Freeze {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        who: Account,
+    },
+    /// Thaws `who` account, signed by the admin.
+    Thaw {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        who: Account,
+    },
+    FreezeAsset {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+    },
+    ThawAsset {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+    },
+    TransferOwnership {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        owner: Account,
+    },
+    SetTeam {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        issuer: Account,
+        #[clap(long, value_parser)]
+        admin: Account,
+        #[clap(long, value_parser)]
+        freezer: Account,
+    },
+    SetMetadata {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        name: String,
+        #[clap(long, value_parser)]
+        symbol: String,
+        #[clap(long, value_parser)]
+        decimals: u8,
+    },
+    ClearMetadata {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+    },
+    ForceSetMetadata {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        name: String,
+        #[clap(long, value_parser)]
+        symbol: String,
+        #[clap(long, value_parser)]
+        decimals: u8,
+        #[clap(long, action)]
+        is_frozen: bool,
+    },
+    ForceClearMetadata {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+    },
+    ForceAssetStatus {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        owner: Account,
+        #[clap(long, value_parser)]
+        issuer: Account,
+        #[clap(long, value_parser)]
+        admin: Account,
+        #[clap(long, value_parser)]
+        freezer: Account,
+        #[clap(long, value_parser)]
+        min_balance: u128,
+        #[clap(long, action)]
+        is_sufficient: bool,
+        #[clap(long, action)]
+        is_frozen: bool,
+    },
+    ApproveTransfer {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        delegate: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+    CancelApproval {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        delegate: Account,
+    },
+    ForceCancelApproval {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        owner: Account,
+        #[clap(long, value_parser)]
+        delegate: Account,
+    },
+    /// Transfers from `owner` to `destination`, signed by the delegate.
+    TransferApproved {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        id: u32,
+        #[clap(long, value_parser)]
+        owner: Account,
+        #[clap(long, value_parser)]
+        destination: Account,
+        #[clap(long, value_parser)]
+        amount: u128,
+    },
+}
+
+/// `pallet_deip_f_nft` extrinsics: collections are 0x prefixed H160 ids,
+/// items are 0x prefixed H256 fingerprints.
+#[derive(Subcommand)]
+pub enum DeipFNFTCall {
+    /// The signer becomes the owner.
+    CreateCollection {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        collection: H160,
+        /// Unlimited if not given.
+        #[clap(short, long, value_parser)]
+        max_items: Option<u32>,
+    },
+    MintItem {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        collection: H160,
+        #[clap(short, long, value_parser = parse_h256)]
+        item: H256,
+    },
+    TransferItem {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h256)]
+        item: H256,
+        #[clap(long, value_parser)]
+        to: Account,
+    },
+    MintFraction {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h256)]
+        item: H256,
+        #[clap(short, long, value_parser)]
+        amount: u128,
+    },
+    BurnFraction {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h256)]
+        item: H256,
+        #[clap(short, long, value_parser)]
+        amount: u128,
+    },
+    TransferFraction {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h256)]
+        item: H256,
+        #[clap(long, value_parser)]
+        to: Account,
+        #[clap(short, long, value_parser)]
+        amount: u128,
+    },
+    FractionalizeItem {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h256)]
+        item: H256,
+        #[clap(short, long, value_parser)]
+        total_amount: u128,
+        /// Forbids minting fractions beyond `total_amount`.
+        #[clap(long, action)]
+        limited: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DeipCall {
+    Project {
+        #[clap(subcommand)]
+        call: DeipProjectCall,
+    },
+    Contract {
+        #[clap(subcommand)]
+        call: DeipContractCall,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DeipProjectCall {
+    Create {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        /// Owning team, the signer or a DAO it acts for.
+        #[clap(short, long, value_parser)]
+        team: DeipAccount,
+        /// Hash of the off-chain description.
+        #[clap(short, long, value_parser = parse_h256)]
+        description: H256,
+        #[clap(long, value_parser = parse_h160, use_value_delimiter = true)]
+        domains: Vec<H160>,
+        #[clap(long, action)]
+        private: bool,
+    },
+    /// Changes the given fields only.
+    Update {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_parser = parse_h256)]
+        description: Option<H256>,
+        #[clap(long, value_parser)]
+        private: Option<bool>,
+    },
+    /// Publishes content of a project.
+    Content {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_parser = parse_h160)]
+        project: H160,
+        #[clap(short, long, value_parser)]
+        team: DeipAccount,
+        #[clap(long = "type", value_enum)]
+        content_type: ProjectContentType,
+        #[clap(short, long, value_parser = parse_h256)]
+        description: H256,
+        /// Hash of the content itself.
+        #[clap(short, long, value_parser = parse_h256)]
+        content: H256,
+        #[clap(short, long, value_parser, use_value_delimiter = true)]
+        authors: Vec<DeipAccount>,
+        /// Ids of referenced project contents.
+        #[clap(long, value_parser = parse_h160, use_value_delimiter = true)]
+        references: Option<Vec<H160>>,
+    },
+    /// Reviews a project content.
+    Review {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_parser)]
+        author: DeipAccount,
+        /// Hash of the review text.
+        #[clap(short, long, value_parser = parse_h256)]
+        content: H256,
+        #[clap(long, value_parser = parse_h160, use_value_delimiter = true)]
+        domains: Vec<H160>,
+        #[clap(long, value_parser, default_value_t = 0)]
+        assessment_model: u32,
+        /// Assessment weight as `0x` prefixed bytes.
+        #[clap(short, long, value_parser = parse_bytes, default_value = "0x")]
+        weight: Vec<u8>,
+        #[clap(short, long, value_parser = parse_h160)]
+        project_content: H160,
+    },
+    /// Upvotes a review in one of its domains.
+    Upvote {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        review: H160,
+        #[clap(short, long, value_parser = parse_h160)]
+        domain: H160,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DeipContractCall {
+    /// Proposes an agreement to `parties`, each of them has to accept it.
+    Create {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_parser)]
+        creator: DeipAccount,
+        #[clap(short, long, value_parser, use_value_delimiter = true)]
+        parties: Vec<DeipAccount>,
+        /// Hash of the agreement document.
+        #[clap(long, value_parser = parse_h256)]
+        hash: H256,
+        /// Unix time in milliseconds.
+        #[clap(long, value_parser)]
+        activation_time: Option<u64>,
+        /// Unix time in milliseconds.
+        #[clap(long, value_parser)]
+        expiration_time: Option<u64>,
+        #[clap(short, long, value_enum, default_value = "generic-contract-agreement")]
+        terms: ContractTerms,
+    },
+    /// Accepts as `party`, the last acceptance finalizes the agreement.
+    Accept {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_parser)]
+        party: DeipAccount,
+    },
+    Reject {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_parser)]
+        party: DeipAccount,
+    },
+}
+
+/// `pallet_deip::contract::Terms` and its `IndexTerms`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractTerms {
+    GenericContractAgreement,
+}
+
+#[derive(Subcommand)]
+pub enum DeipProposalCall {
+    /// Proposes a batch of calls, dispatched once every account involved
+    /// approved. `--call` items come first, then those of `--batch`.
+    Propose {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        /// Derived by the pallet if not given.
+        #[clap(short, long, value_parser = parse_h160)]
+        id: Option<H160>,
+        #[clap(short, long = "call", value_parser)]
+        calls: Vec<BatchCall>,
+        /// `.toml` or `.yaml` file of `[[items]]`, each with either a `run`
+        /// tx command or a hex `call`, and the `account` dispatching it,
+        /// by default the command's signer.
+        #[clap(short, long, value_parser)]
+        batch: Option<PathBuf>,
+    },
+    /// Approves or rejects a proposal as the signer.
+    Decide {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(short, long, value_enum)]
+        decision: Decision,
+        /// The weight stored with the proposal if not given.
+        #[clap(short, long, value_parser)]
+        weight: Option<u64>,
+    },
+    /// Removes a proposal past its expiration time.
+    Expire {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+    },
+}
+
+/// Calls on behalf of a DAO are signed by its authority key.
+#[derive(Subcommand)]
+pub enum DeipDaoCall {
+    /// The signer becomes the authority key.
+    Create {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        id: H160,
+        #[clap(long, value_parser, use_value_delimiter = true)]
+        signatories: Vec<Account>,
+        /// Signatures required out of `signatories`.
+        #[clap(short, long, value_parser)]
+        threshold: u16,
+        /// Hash of the off-chain metadata.
+        #[clap(short, long, value_parser = parse_h256)]
+        metadata: Option<H256>,
+    },
+    /// Changes the authority, on behalf of the DAO.
+    AlterAuthority {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        dao: H160,
+        #[clap(subcommand)]
+        change: AuthorityChange,
+    },
+    /// Replaces the metadata hash, on behalf of the DAO.
+    Update {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        dao: H160,
+        /// Cleared if not given.
+        #[clap(short, long, value_parser = parse_h256)]
+        metadata: Option<H256>,
+    },
+    /// Dispatches a call with the DAO account as origin.
+    OnBehalf {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser = parse_h160)]
+        dao: H160,
+        /// Hex encoded runtime call.
+        #[clap(short, long, value_parser = parse_bytes, required_unless_present = "run")]
+        call: Option<Vec<u8>>,
+        /// A tx command to encode as the call, e.g. "tx deip project create ...".
+        #[clap(short, long, value_parser, conflicts_with = "call")]
+        run: Option<String>,
+    },
+}
+
+/// Times are Timestamp pallet milliseconds.
+#[derive(Subcommand)]
+pub enum DeipVestingCall {
+    /// Transfers `amount` to `target`, locked and released by a vesting plan.
+    VestedTransfer {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+        #[clap(short, long, value_parser)]
+        target: Account,
+        /// Start of the plan, the best block's timestamp by default.
+        #[clap(long, value_parser)]
+        start: Option<u64>,
+        /// Time after the start before more than the initial amount is released.
+        #[clap(long, value_parser, default_value_t = 0)]
+        cliff: u64,
+        /// Time after the start when the whole amount is released.
+        #[clap(short, long, value_parser)]
+        duration: u64,
+        /// Time between two releases.
+        #[clap(short = 'i', long = "release-interval", value_parser)]
+        release_interval: u64,
+        /// Total amount, the initial amount included.
+        #[clap(short, long, value_parser)]
+        amount: u128,
+        /// Released at the start.
+        #[clap(long, value_parser, default_value_t = 0)]
+        initial: u128,
+        /// Releases at the end of the cliff what vested during it, instead of
+        /// starting the releases then.
+        #[clap(long, action)]
+        vesting_during_cliff: bool,
+    },
+    /// Unlocks what vested of the signer's plan.
+    Unlock {
+        #[clap(short, long, alias = "suri", value_parser)]
+        signer: Suri,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuthorityChange {
+    AddMember {
+        #[clap(short, long, value_parser)]
+        member: Account,
+        /// Keeps the threshold instead of raising it with the member count.
+        #[clap(long, action)]
+        preserve_threshold: bool,
+    },
+    RemoveMember {
+        #[clap(short, long, value_parser)]
+        member: Account,
+        /// Keeps the threshold instead of lowering it with the member count.
+        #[clap(long, action)]
+        preserve_threshold: bool,
+    },
+    /// Hands the DAO over to a new authority key and signatories.
+    Replace {
+        #[clap(short, long, value_parser)]
+        authority_key: Account,
+        #[clap(long, value_parser, use_value_delimiter = true)]
+        signatories: Vec<Account>,
+        #[clap(short, long, value_parser)]
+        threshold: u16,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Decision {
+    Approve,
+    Reject,
+}
+
+/// Proposal batch item as `ACCOUNT=0xCALL`: a hex encoded runtime call and
+/// the account, SS58 address or DAO id dispatching it.
+#[derive(Clone)]
+pub struct BatchCall {
+    pub account: DeipAccount,
+    pub call: Vec<u8>,
+}
+
+impl FromStr for BatchCall {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (account, call) = s.split_once('=').ok_or("expected ACCOUNT=0xCALL")?;
+        Ok(Self {
+            account: account.parse()?,
+            call: parse_bytes(call)?,
+        })
+    }
+}
+
+/// `pallet_deip::ProjectContentType`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ProjectContentType {
+    Announcement,
+    FinalResult,
+    MilestoneArticle,
+    MilestoneBook,
+    MilestoneChapter,
+    MilestoneCode,
+    MilestoneConferencePaper,
+    MilestoneCoverPage,
+    MilestoneData,
+    MilestoneExperimentFindings,
+    MilestoneMethod,
+    MilestoneNegativeResults,
+    MilestonePatent,
+    MilestonePoster,
+    MilestonePreprint,
+    MilestonePresentation,
+    MilestoneRawData,
+    MilestoneResearchProposal,
+    MilestoneTechnicalReport,
+    MilestoneThesis,
+}
+
+#[derive(Subcommand)]
+pub enum SudoCall {
     SudoUncheckedWeight {
+        /// Sudo key.
         #[clap(short, value_parser)]
-        account: Account,
+        account: Suri,
+        /// Runtime blob to set as the new code, plain or zstd-compressed wasm.
+        #[clap(long, value_parser)]
+        wasm: PathBuf,
     },
 }
 
-#[derive(ValueEnum, Clone)]
-pub enum Account {
-    Alice,
+/// Extrinsic signer: a dev account name (alice, bob, ..., one, two) or a
+/// secret URI such as a mnemonic, a hex seed or `//Alice//stash`.
+#[derive(Clone)]
+pub struct Suri(pub Pair);
+
+impl FromStr for Suri {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(keyring) = dev_account(s) {
+            return Ok(Self(keyring.pair()));
+        }
+        Pair::from_string(s, None)
+            .map(Self)
+            .map_err(|err| format!("neither a dev account nor a valid secret URI: {err:?}"))
+    }
+}
+
+/// Call parameter account: a dev account name or an SS58 address.
+#[derive(Clone)]
+pub struct Account(pub AccountId32);
+
+impl FromStr for Account {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(keyring) = dev_account(s) {
+            return Ok(Self(keyring.to_account_id()));
+        }
+        AccountId32::from_ss58check(s)
+            .map(Self)
+            .map_err(|err| format!("neither a dev account nor an SS58 address: {err:?}"))
+    }
 }
 
-impl From<Account> for AccountKeyring {
+impl From<Account> for MultiAddress<AccountId32, ()> {
     fn from(account: Account) -> Self {
-        match account {
-            Account::Alice => AccountKeyring::Alice,
+        account.0.into()
+    }
+}
+
+/// `DeipAccountId` parameter: a DAO id as 0x prefixed hex, otherwise an account.
+#[derive(Clone)]
+pub enum DeipAccount {
+    Native(AccountId32),
+    Dao(H160),
+}
+
+impl FromStr for DeipAccount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") {
+            return parse_h160(s).map(Self::Dao);
         }
+        s.parse().map(|account: Account| Self::Native(account.0))
     }
 }
+
+fn dev_account(name: &str) -> Option<AccountKeyring> {
+    AccountKeyring::iter().find(|keyring| format!("{keyring:?}").eq_ignore_ascii_case(name))
+}
@@ -1,10 +1,14 @@
-use std::str::FromStr;
+use serde::Deserialize;
+use std::{str::FromStr, time::Duration};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Interval {
     None,
     Timer,
     Input,
+    /// Waits for new finalized blocks.
+    Finalized,
 }
 
 impl FromStr for Interval {
@@ -15,7 +19,18 @@ impl FromStr for Interval {
             "none" => Ok(Self::None),
             "timer" => Ok(Self::Timer),
             "input" => Ok(Self::Input),
-            _ => Err("possible values: none, timer, input"),
+            "finalized" => Ok(Self::Finalized),
+            _ => Err("possible values: none, timer, input, finalized"),
         }
     }
 }
+
+/// Pause taken after each step of a flow.
+#[derive(Debug, Clone, Copy)]
+pub struct Pacing {
+    pub interval: Interval,
+    /// Sleep of `Interval::Timer`.
+    pub duration: Duration,
+    /// Block count of `Interval::Finalized`.
+    pub blocks: u32,
+}
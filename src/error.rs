@@ -0,0 +1,92 @@
+use std::fmt::{self, Display};
+use subxt::BasicError;
+
+/// Exit codes, listed in `--help`.
+pub const EXIT_CODES: &str = "\
+EXIT CODES:
+    0  success
+    1  any other failure, e.g. a failed expectation
+    2  invalid arguments
+    3  connection: the node is unreachable or dropped the connection
+    4  encoding: the call does not match the node's metadata
+    5  rejected: the transaction pool refused the extrinsic
+    6  dispatch: the extrinsic was included but failed
+    7  decoding: storage or events do not decode with the generated types";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Connection,
+    Encoding,
+    Rejected,
+    Dispatch,
+    Decoding,
+}
+
+impl ErrorKind {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Connection => 3,
+            Self::Encoding => 4,
+            Self::Rejected => 5,
+            Self::Dispatch => 6,
+            Self::Decoding => 7,
+        }
+    }
+
+    /// Tags an error with this kind, for `map_err`.
+    pub fn wrap<E: Display>(self) -> impl FnOnce(E) -> Error {
+        move |err| Error {
+            kind: self,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// A failure the CLI reports with its own exit code.
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            ErrorKind::Connection => "connection failed",
+            ErrorKind::Encoding => "encoding failed",
+            ErrorKind::Rejected => "transaction rejected",
+            ErrorKind::Dispatch => "dispatch failed",
+            ErrorKind::Decoding => "decoding failed",
+        };
+        write!(f, "{kind}: {}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Kind of the first tagged error in the chain, or of an untagged subxt or
+/// codec error by its variant.
+pub fn kind(err: &anyhow::Error) -> Option<ErrorKind> {
+    err.chain().find_map(|cause| {
+        if let Some(err) = cause.downcast_ref::<Error>() {
+            return Some(err.kind);
+        }
+        if cause.is::<parity_scale_codec::Error>() {
+            return Some(ErrorKind::Decoding);
+        }
+        match cause.downcast_ref::<BasicError>()? {
+            BasicError::Io(_) | BasicError::Rpc(_) => Some(ErrorKind::Connection),
+            BasicError::Metadata(_) | BasicError::InvalidMetadata(_) => Some(ErrorKind::Encoding),
+            BasicError::Codec(_) | BasicError::Serialization(_) | BasicError::EventsDecoding(_) => {
+                Some(ErrorKind::Decoding)
+            }
+            BasicError::Transaction(_) => Some(ErrorKind::Rejected),
+            BasicError::Module(_) | BasicError::Runtime(_) => Some(ErrorKind::Dispatch),
+            BasicError::Other(_) => None,
+        }
+    })
+}
+
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    kind(err).map_or(1, ErrorKind::exit_code)
+}
@@ -0,0 +1,149 @@
+use anyhow::{bail, Result};
+use futures::{pin_mut, Stream, StreamExt};
+use parity_scale_codec::Encode;
+use serde::Serialize;
+use std::time::Duration;
+use subxt::{sp_core::H256, BasicError, DefaultConfig, EventDetails, Events};
+
+use crate::{
+    app::{DeipRuntime, EventRecord, Runtime},
+    cli::{Endpoints, EventFilter, RuntimeVersion, WaitFor},
+    output,
+};
+
+/// Pause before reconnecting after the node dropped the connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const CODE_UPDATED: &str = "System::CodeUpdated";
+
+/// Matching events of one block.
+#[derive(Serialize)]
+struct BlockEvents {
+    block_hash: H256,
+    block_number: Option<u32>,
+    events: Vec<EventRecord>,
+}
+
+/// Prints the events of every block until interrupted. A dropped connection
+/// or a runtime upgrade leads to a new connection, detecting the runtime again.
+pub async fn watch(
+    endpoints: &Endpoints,
+    force: Option<RuntimeVersion>,
+    wait: WaitFor,
+    finalized: bool,
+    filter: &EventFilter,
+) -> Result<()> {
+    let mut runtime = Runtime::connect(endpoints, force, wait).await?;
+    loop {
+        let result = match &runtime {
+            Runtime::Master(app) => follow(app, finalized, filter).await,
+            Runtime::Develop(app) => follow(app, finalized, filter).await,
+        };
+        match result {
+            Ok(()) => info!("Runtime upgraded, reconnecting"),
+            Err(err) => {
+                warn!("{err:#}, reconnecting in {RECONNECT_DELAY:?}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+        runtime = loop {
+            match Runtime::connect(endpoints, force, wait).await {
+                Ok(runtime) => break runtime,
+                Err(err) => {
+                    warn!("{err:#}, retrying in {RECONNECT_DELAY:?}");
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        };
+    }
+}
+
+/// Follows blocks until the runtime is upgraded, or fails.
+async fn follow<R: DeipRuntime>(app: &R, finalized: bool, filter: &EventFilter) -> Result<()> {
+    if finalized {
+        follow_blocks(app, app.subscribe_finalized_events().await?, filter).await
+    } else {
+        follow_blocks(app, app.subscribe_events().await?, filter).await
+    }
+}
+
+async fn follow_blocks<R, S>(app: &R, blocks: S, filter: &EventFilter) -> Result<()>
+where
+    R: DeipRuntime,
+    S: Stream<Item = Result<Events<DefaultConfig, R::Event>, BasicError>>,
+{
+    pin_mut!(blocks);
+    while let Some(events) = blocks.next().await {
+        let events = events?;
+        let block_hash = events.block_hash();
+        let mut matched = Vec::new();
+        let mut upgraded = false;
+        for event in events.iter() {
+            let event = event?;
+            let name = format!("{}::{}", event.pallet, event.variant);
+            upgraded |= name == CODE_UPDATED;
+            if matches(filter, &event) {
+                matched.push(EventRecord::new(
+                    app,
+                    &app.client().metadata().read(),
+                    event,
+                ));
+            }
+        }
+        if !matched.is_empty() {
+            let header = app.client().rpc().header(Some(block_hash)).await?;
+            let block = BlockEvents {
+                block_hash,
+                block_number: header.map(|header| header.number),
+                events: matched,
+            };
+            output::emit(&block, display_block)?;
+        }
+        if upgraded {
+            return Ok(());
+        }
+    }
+    bail!("Event subscription closed")
+}
+
+/// Pallet and event names compare case-insensitively. An account matches
+/// when its 32 bytes appear anywhere in the SCALE encoded event.
+fn matches<E: Encode>(filter: &EventFilter, event: &EventDetails<E>) -> bool {
+    let pallet_matches = filter.pallets.is_empty()
+        || filter
+            .pallets
+            .iter()
+            .any(|pallet| pallet.eq_ignore_ascii_case(&event.pallet));
+    let event_matches = filter.events.is_empty()
+        || filter
+            .events
+            .iter()
+            .any(|name| match name.split_once("::") {
+                Some((pallet, variant)) => {
+                    pallet.eq_ignore_ascii_case(&event.pallet)
+                        && variant.eq_ignore_ascii_case(&event.variant)
+                }
+                None => name.eq_ignore_ascii_case(&event.variant),
+            });
+    let account_matches = filter.account.as_ref().is_none_or(|account| {
+        let account: &[u8] = account.0.as_ref();
+        event
+            .event
+            .encode()
+            .windows(account.len())
+            .any(|window| window == account)
+    });
+    pallet_matches && event_matches && account_matches
+}
+
+fn display_block(block: &BlockEvents) {
+    let block_number = block.block_number.map(|number| format!("#{number} "));
+    info!(
+        "Block {}{:?}",
+        block_number.unwrap_or_default(),
+        block.block_hash
+    );
+    for event in &block.events {
+        info!(" - {}", event.details);
+    }
+}
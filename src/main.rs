@@ -1,168 +1,308 @@
 #![allow(clippy::too_many_arguments)]
 
 mod app;
+mod block;
 mod cli;
 mod config;
+mod error;
+mod events;
+mod migration;
+mod output;
+mod proposal;
 mod runtime;
+mod scenario;
+mod snapshot;
+mod vesting;
+mod wasm;
 
 #[macro_use]
 extern crate log;
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
 use clap::Parser;
+use futures::StreamExt;
 use log::LevelFilter;
-use parity_scale_codec::Decode;
-use sp_keyring::AccountKeyring;
-use std::{path::Path, time::Duration};
+use serde_json::json;
+use std::{path::Path, process::ExitCode};
+use subxt::{Client, DefaultConfig};
 
 use crate::{
-    app::{
-        develop::App as DevelopApp,
-        master::{display_event as master_display_event, App as MasterApp},
-    },
+    app::{block_hash, AssetInfo, DeipRuntime, Runtime},
     cli::{
-        Args, AssetsStorage, Call as CliCall, DeipFNFTStorage, EndPoint, PalletCall, PalletStorage,
-        RuntimeVersion,
-    },
-    config::Interval,
-    runtime::master_v104::api::runtime_types::{
-        appchain_deip_runtime::Call, frame_system::pallet::Call as SystemCall,
+        Args, AssetsStorage, BlockCommand, EndPoint, EventsCommand, MigrationStep, PalletCall,
+        PalletStorage, SudoCall, VestingCommand,
     },
+    config::{Interval, Pacing},
+    migration::PreMigration,
+    snapshot::Snapshot,
+    wasm::RuntimeWasm,
 };
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let args = Args::parse();
     env_logger::builder().filter_level(LevelFilter::Info).init();
+    output::init(args.format);
     info!("Hello, DEIP world!");
 
-    match args.runtime_version {
-        RuntimeVersion::Master { endpoint } => {
-            let app = MasterApp::build().await.unwrap();
-            info!("App built [master]");
-            let tx = app.client.tx();
-            match endpoint {
-                EndPoint::Tx { pallet } => match pallet {
-                    PalletCall::Assets { call } => {
-                        let assets = tx.assets();
-                        match call {
-                            CliCall::Create {
-                                id,
-                                admin,
-                                min_balance,
-                            } => {
-                                let admin: AccountKeyring = admin.into();
-                                assets
-                                    .create(id, admin.to_account_id().into(), min_balance)
-                                    .unwrap()
-                                    .sign_and_submit_then_watch_default(&app.signer(admin))
-                                    .await
-                                    .unwrap()
-                                    .wait_for_in_block()
-                                    .await
-                                    .unwrap()
-                                    .fetch_events()
-                                    .await
-                                    .unwrap()
-                                    .iter()
-                                    .map(|event| event.unwrap())
-                                    .for_each(master_display_event);
-                            }
-                            _ => todo!(),
-                        }
-                    }
-                    PalletCall::Sudo { call } => {
-                        let sudo = tx.sudo();
-                        match call {
-                            CliCall::SudoUncheckedWeight { account } => sudo
-                                .sudo_unchecked_weight(runtime_upgrade().await, 0)
-                                .unwrap()
-                                .sign_and_submit_then_watch_default(&app.signer(account.into()))
-                                .await
-                                .unwrap()
-                                .wait_for_in_block()
-                                .await
-                                .unwrap()
-                                .fetch_events()
-                                .await
-                                .unwrap()
-                                .iter()
-                                .map(|event| event.unwrap())
-                                .for_each(master_display_event),
-                            _ => todo!(),
-                        }
-                    }
-                },
-                EndPoint::Storage { pallet } => match pallet {
-                    PalletStorage::Assets { storage } => match storage {
-                        AssetsStorage::Asset { key } => {
-                            let mut key_iter = app
-                                .client
-                                .storage()
-                                .assets()
-                                .asset_iter(None)
-                                .await
-                                .unwrap();
-                            while let Some((storage_key, _)) = key_iter.next().await.unwrap() {
-                                let mut storage_key = &storage_key.0[48..52];
-                                let storage_key = u32::decode(&mut storage_key).unwrap();
-                                info!("key: {key} - storage_key: {storage_key:?}");
-                                assert_eq!(storage_key, key);
-                            }
-                        }
-                    },
-                    PalletStorage::DeipFNFT { storage: _ } => todo!(),
-                },
+    match execute(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            error!("{err:#}");
+            ExitCode::from(error::exit_code(&err))
+        }
+    }
+}
+
+async fn execute(args: Args) -> Result<()> {
+    let pacing = args.pacing();
+    match args.endpoint {
+        EndPoint::Diff { pre, post } => diff(&pre, &post).await?,
+        EndPoint::Run { scenario: path } => {
+            scenario::run(
+                &path,
+                &args.endpoints,
+                args.force_runtime,
+                args.wait,
+                pacing,
+            )
+            .await?
+        }
+        EndPoint::Events {
+            command: EventsCommand::Watch { finalized, filter },
+        } => {
+            events::watch(
+                &args.endpoints,
+                args.force_runtime,
+                args.wait,
+                finalized,
+                &filter,
+            )
+            .await?
+        }
+        endpoint => {
+            let runtime = Runtime::connect(&args.endpoints, args.force_runtime, args.wait).await?;
+            match &runtime {
+                Runtime::Master(app) => run(app, endpoint).await?,
+                Runtime::Develop(app) => run(app, endpoint).await?,
             }
+            wait_for(runtime.client(), pacing).await?;
         }
-        RuntimeVersion::Develop { endpoint } => {
-            let app = DevelopApp::build().await.unwrap();
-            info!("App built [develop]");
-            match endpoint {
-                EndPoint::Tx { pallet: _ } => todo!(),
-                EndPoint::Storage { pallet } => match pallet {
-                    PalletStorage::Assets { storage } => match storage {
-                        AssetsStorage::Asset { key } => {
-                            let mut key_iter = app
-                                .client
-                                .storage()
-                                .assets()
-                                .asset_iter(None)
-                                .await
-                                .unwrap();
-                            while let Some((storage_key, _)) = key_iter.next().await.unwrap() {
-                                let mut storage_key = &storage_key.0[48..52];
-                                let storage_key = u32::decode(&mut storage_key).unwrap();
-                                info!("key: {key} - storage_key: {storage_key:?}");
-                                assert_eq!(storage_key, key);
-                            }
-                        }
-                    },
-                    PalletStorage::DeipFNFT { storage } => todo!(),
+    }
+
+    Ok(())
+}
+
+async fn run<R: DeipRuntime>(app: &R, endpoint: EndPoint) -> Result<()> {
+    info!("App built [{}]", R::NAME);
+    match endpoint {
+        EndPoint::Tx { pallet } => match pallet {
+            PalletCall::Assets { call } => app.assets_tx(call).await?,
+            PalletCall::DeipFNFT { call } => app.deip_fnft_tx(call).await?,
+            PalletCall::Deip { call } => app.deip_tx(call).await?,
+            PalletCall::DeipProposal { call } => app.deip_proposal_tx(call).await?,
+            PalletCall::DeipDao { call } => app.deip_dao_tx(call).await?,
+            PalletCall::DeipVesting { call } => app.deip_vesting_tx(call).await?,
+            PalletCall::Sudo { call } => match call {
+                SudoCall::SudoUncheckedWeight { account, wasm } => {
+                    let call = runtime_upgrade::<R>(&wasm).await?;
+                    app.sudo_unchecked_weight(account, call).await?
+                }
+            },
+        },
+        EndPoint::Storage { pallet } => match pallet {
+            PalletStorage::Assets { storage } => match storage {
+                AssetsStorage::Asset { key } => match app.asset(key).await? {
+                    Some(asset) => display_asset(key, &asset)?,
+                    None => bail!("Asset {key} not found"),
                 },
-            }
+                AssetsStorage::List => {
+                    let assets = app.assets().await?;
+                    info!("{} assets", assets.len());
+                    for (id, asset) in &assets {
+                        display_asset(*id, asset)?;
+                    }
+                }
+            },
+            PalletStorage::DeipFNFT { storage } => app.deip_fnft_storage(storage).await?,
+            PalletStorage::Deip { storage } => app.deip_storage(storage).await?,
+            PalletStorage::DeipProposal { storage } => app.deip_proposal_storage(storage).await?,
+            PalletStorage::DeipDao { storage } => app.deip_dao_storage(storage).await?,
+        },
+        EndPoint::Snapshot {
+            output: path,
+            at,
+            pallets,
+        } => {
+            let at = block_hash(app.client(), at).await?;
+            let snapshot = Snapshot::take(app.client(), &pallets, at).await?;
+            snapshot.save(&path).await?;
+            let saved = json!({ "block_hash": at, "output": path });
+            output::emit(&saved, |_| {
+                info!("Snapshot of block {at:?} saved to {}", path.display())
+            })?;
         }
+        EndPoint::VerifyMigration { step } => match step {
+            MigrationStep::Capture { output: path } => {
+                let pre = app.capture_migration().await?;
+                pre.save(&path).await?;
+                let captured = json!({
+                    "block_hash": pre.block_hash,
+                    "assets": pre.assets.len(),
+                    "deip_assets": pre.deip_assets.len(),
+                    "nft_classes": pre.nft_classes.len(),
+                    "ft_balances": pre.ft_balances.len(),
+                    "asset_metadata": pre.asset_metadata.len(),
+                    "nft_balances": pre.nft_balances.len(),
+                    "output": path,
+                });
+                output::emit(&captured, |_| {
+                    info!(
+                        "Captured {} assets, {} deip assets ({} with holders, {} with metadata) \
+                         and {} nft classes ({} with holders) at {} into {}",
+                        pre.assets.len(),
+                        pre.deip_assets.len(),
+                        pre.ft_balances.len(),
+                        pre.asset_metadata.len(),
+                        pre.nft_classes.len(),
+                        pre.nft_balances.len(),
+                        pre.block_hash,
+                        path.display(),
+                    )
+                })?;
+            }
+            MigrationStep::Check { input } => {
+                let pre = PreMigration::load(&input).await?;
+                let mismatches = app.verify_migration(&pre).await?;
+                output::emit(&json!({ "mismatches": mismatches }), |_| {
+                    mismatches
+                        .iter()
+                        .for_each(|mismatch| error!(" - {mismatch}"))
+                })?;
+                ensure!(
+                    mismatches.is_empty(),
+                    "Migration check failed: {} mismatches",
+                    mismatches.len()
+                );
+                info!("Migration check passed");
+            }
+        },
+        EndPoint::Block {
+            command: BlockCommand::Show { at },
+        } => block::show(app, at).await?,
+        EndPoint::Vesting {
+            command: VestingCommand::Show { account, releases },
+        } => vesting::show(app, &account.0, releases).await?,
+        EndPoint::Diff { .. } => unreachable!("diff does not need a node connection"),
+        EndPoint::Run { .. } => unreachable!("scenarios manage their own connection"),
+        EndPoint::Events { .. } => unreachable!("events watch manages its own connection"),
     }
+    Ok(())
+}
 
+async fn diff(pre: &Path, post: &Path) -> Result<()> {
+    let pre = Snapshot::load(pre).await?;
+    let post = Snapshot::load(post).await?;
+    info!(
+        "Diff {} (spec_version {}, block {}) -> {} (spec_version {}, block {})",
+        pre.runtime,
+        pre.spec_version,
+        pre.block_hash,
+        post.runtime,
+        post.spec_version,
+        post.block_hash,
+    );
+    for map in snapshot::diff(&pre, &post) {
+        output::emit(&map, |map| {
+            info!(
+                "{}::{}: {} added, {} removed, {} changed",
+                map.pallet,
+                map.entry,
+                map.added.len(),
+                map.removed.len(),
+                map.changed.len(),
+            );
+            map.added.iter().for_each(|key| info!("   + {key}"));
+            map.removed.iter().for_each(|key| info!("   - {key}"));
+            map.changed.iter().for_each(|key| info!("   ~ {key}"));
+        })?;
+    }
     Ok(())
 }
 
-async fn _wait_for(interval: Interval, duration: Option<Duration>) {
-    match interval {
+fn display_asset(id: u32, asset: &AssetInfo) -> Result<()> {
+    output::emit(&json!({ "id": id, "asset": asset }), |_| {
+        display_asset_text(id, asset)
+    })
+}
+
+fn display_asset_text(id: u32, asset: &AssetInfo) {
+    info!("Asset {id}:");
+    info!("   owner: {}", asset.owner);
+    info!("   issuer: {}", asset.issuer);
+    info!("   admin: {}", asset.admin);
+    info!("   freezer: {}", asset.freezer);
+    info!("   supply: {}", asset.supply);
+    info!("   deposit: {}", asset.deposit);
+    info!("   min_balance: {}", asset.min_balance);
+    info!("   accounts: {}", asset.accounts);
+    info!("   sufficients: {}", asset.sufficients);
+    info!("   approvals: {}", asset.approvals);
+    info!(
+        "   status: {}{}",
+        if asset.is_frozen { "frozen" } else { "live" },
+        if asset.is_sufficient {
+            ", sufficient"
+        } else {
+            ""
+        },
+    );
+}
+
+/// Pauses as `pacing` says, between the steps of a flow.
+async fn wait_for(client: &Client<DefaultConfig>, pacing: Pacing) -> Result<()> {
+    match pacing.interval {
+        Interval::None => {}
         Interval::Input => {
-            let _ = std::io::Read::read(&mut std::io::stdin(), &mut [0u8]).unwrap();
+            info!("Press enter to continue");
+            std::io::stdin().read_line(&mut String::new())?;
+        }
+        Interval::Timer => {
+            info!("Waiting {:?}", pacing.duration);
+            tokio::time::sleep(pacing.duration).await
+        }
+        Interval::Finalized => {
+            let mut subscription = client.rpc().subscribe_finalized_blocks().await?;
+            let mut target = None;
+            while let Some(header) = subscription.next().await {
+                let number = header?.number;
+                let target = *target.get_or_insert(number + pacing.blocks);
+                info!("Finalized #{number}");
+                if number >= target {
+                    break;
+                }
+            }
         }
-        Interval::None => {}
-        Interval::Timer => tokio::time::sleep(duration.unwrap()).await,
     }
+    Ok(())
 }
 
-async fn runtime_upgrade() -> Call {
-    let path = "/Users/piakushin/rust/deip-node/target/release/wbuild/appchain-deip-runtime/appchain_deip_runtime.compact.compressed.wasm";
-    let path = Path::new(path);
-    println!("{}", path.display());
-    let path = path.canonicalize().unwrap();
-    let code = tokio::fs::read(path).await.unwrap();
-    let call = SystemCall::set_code { code };
-    Call::System(call)
+async fn runtime_upgrade<R: DeipRuntime>(path: &Path) -> Result<R::Call> {
+    let wasm = RuntimeWasm::load(path).await?;
+    let summary = json!({
+        "wasm": path,
+        "size": wasm.code.len(),
+        "uncompressed_size": wasm.uncompressed_size,
+        "blake2_256": wasm.hash,
+    });
+    output::emit(&summary, |_| {
+        info!(
+            "Runtime wasm {}: {} bytes ({} bytes uncompressed), blake2_256: {:?}",
+            path.display(),
+            wasm.code.len(),
+            wasm.uncompressed_size,
+            wasm.hash,
+        )
+    })?;
+    Ok(R::set_code(wasm.code))
 }
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+use crate::snapshot::{read_json, write_json};
+
+/// `DeipAssets`/`DeipUniques` state captured on master before the upgrade.
+///
+/// `verify-migration check` then asserts on develop that:
+/// - every `Assets::Asset` keeps its id, owner and supply, including the
+///   assets referenced by `DeipAssets::AssetIdByDeipAssetId`;
+/// - the asset of every `FtBalanceMap` and `AssetMetadataMap` entry is the
+///   fraction token of a `DeipFNFT::ItemRepo` item;
+/// - every `FtBalanceMap` holder has a `FractionRepo` record of that item
+///   with its `Assets::Account` balance, and as many `FractionHolds` entries
+///   as the record counts holds;
+/// - `Assets::Metadata` keeps the `AssetMetadataMap` name, symbol and decimals;
+/// - every `NftClassIdByDeipNftClassId` entry is a `CollectionRepo` record
+///   with the same id and the uniques class id as `internal_id`, and every
+///   `NftBalanceMap` holder owns an `ItemRepo` item of that collection;
+/// - `NextFTokenId` continues after the migrated assets and
+///   `NextCollectionId` is not below `DeipUniques::NextNftClassId`.
+#[derive(Serialize, Deserialize)]
+pub struct PreMigration {
+    pub block_hash: String,
+    pub assets: BTreeMap<u32, AssetState>,
+    /// `DeipAssets::NextAssetId`.
+    pub next_asset_id: u32,
+    /// `DeipAssets::AssetIdByDeipAssetId`, hex deip asset id to asset id.
+    pub deip_assets: BTreeMap<String, u32>,
+    /// `DeipUniques::NftClassIdByDeipNftClassId`, hex deip class id to class id.
+    pub nft_classes: BTreeMap<String, u32>,
+    /// `DeipUniques::NextNftClassId`.
+    pub next_nft_class_id: u32,
+    /// `DeipAssets::FtBalanceMap`, hex deip asset id to the `Assets::Account`
+    /// balance of each holder by SS58 address.
+    pub ft_balances: BTreeMap<String, BTreeMap<String, u128>>,
+    /// `DeipAssets::AssetMetadataMap`, by hex deip asset id.
+    pub asset_metadata: BTreeMap<String, MetadataState>,
+    /// `DeipUniques::NftBalanceMap`, hex deip class id to SS58 addresses.
+    pub nft_balances: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct AssetState {
+    /// SS58 address.
+    pub owner: String,
+    pub supply: u128,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MetadataState {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl MetadataState {
+    pub fn new(name: &[u8], symbol: &[u8], decimals: u8) -> Self {
+        Self {
+            name: String::from_utf8_lossy(name).into_owned(),
+            symbol: String::from_utf8_lossy(symbol).into_owned(),
+            decimals,
+        }
+    }
+}
+
+impl PreMigration {
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        write_json(path, self).await
+    }
+
+    pub async fn load(path: &Path) -> Result<Self> {
+        read_json(path).await
+    }
+
+    /// Expected `DeipFNFT::NextFTokenId` once the assets are migrated.
+    pub fn next_ftoken_id(&self) -> u32 {
+        let after_assets = self.assets.keys().max().map_or(0, |id| id + 1);
+        after_assets.max(self.next_asset_id)
+    }
+}
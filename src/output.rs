@@ -0,0 +1,26 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use crate::cli::OutputFormat;
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+pub fn init(format: OutputFormat) {
+    FORMAT.set(format).expect("output format is set once");
+}
+
+pub fn is_json() -> bool {
+    FORMAT.get() == Some(&OutputFormat::Json)
+}
+
+/// Prints `value` as one line of JSON on stdout in JSON mode, otherwise
+/// leaves it to `text`, which logs it.
+pub fn emit<T: Serialize>(value: &T, text: impl FnOnce(&T)) -> Result<()> {
+    if is_json() {
+        println!("{}", serde_json::to_string(value)?);
+    } else {
+        text(value);
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use clap::Parser;
+use serde::Deserialize;
+use std::path::Path;
+use subxt::sp_runtime::AccountId32;
+
+use crate::{
+    app::DeipRuntime,
+    cli::{parse_bytes, BatchCall, DeipAccount, EndPoint, StepCommand},
+    scenario::{read_document, CommandLine},
+};
+
+/// Inner calls of a proposal, in dispatch order.
+///
+/// ```toml
+/// [[items]]
+/// run = "tx deip project create -s alice -i 0x1111111111111111111111111111111111111111 ..."
+///
+/// [[items]]
+/// account = "0x2222222222222222222222222222222222222222"
+/// call = "0x3200..."
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Batch {
+    pub items: Vec<BatchItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchItem {
+    /// Dispatching account, SS58 address or DAO id, by default the signer
+    /// of `run`.
+    pub account: Option<String>,
+    /// A `tx` command, encoded instead of submitted.
+    pub run: Option<CommandLine>,
+    /// Hex encoded runtime call.
+    pub call: Option<String>,
+}
+
+/// Encoded calls of `calls` followed by those of the `batch` file, with the
+/// accounts dispatching them.
+pub async fn build_batch<R: DeipRuntime>(
+    app: &R,
+    calls: Vec<BatchCall>,
+    batch: Option<&Path>,
+) -> Result<Vec<(DeipAccount, Vec<u8>)>> {
+    let mut items: Vec<_> = calls
+        .into_iter()
+        .map(|item| (item.account, item.call))
+        .collect();
+    if let Some(path) = batch {
+        let batch: Batch = read_document(path).await?;
+        for (index, item) in batch.items.iter().enumerate() {
+            let context = || format!("{} item {}", path.display(), index + 1);
+            let encoded = encode_item(app, item).await.with_context(context)?;
+            items.extend(encoded);
+        }
+    }
+    ensure!(
+        !items.is_empty(),
+        "Empty proposal, expected --call or --batch"
+    );
+    Ok(items)
+}
+
+async fn encode_item<R: DeipRuntime>(
+    app: &R,
+    item: &BatchItem,
+) -> Result<Vec<(DeipAccount, Vec<u8>)>> {
+    let account = item
+        .account
+        .as_deref()
+        .map(str::parse::<DeipAccount>)
+        .transpose()
+        .map_err(|err| anyhow!("account: {err}"))?;
+    match (&item.run, &item.call) {
+        (Some(command), None) => {
+            let calls = record_calls(app, command).await?;
+            Ok(calls
+                .into_iter()
+                .map(|(signer, call)| {
+                    let account = account.clone().unwrap_or(DeipAccount::Native(signer));
+                    (account, call)
+                })
+                .collect())
+        }
+        (None, Some(call)) => {
+            let account = account.ok_or_else(|| anyhow!("a hex call needs an account"))?;
+            let call = parse_bytes(call).map_err(|err| anyhow!("call: {err}"))?;
+            Ok(vec![(account, call)])
+        }
+        _ => bail!("expected either run or call"),
+    }
+}
+
+/// Signer and encoded call of each extrinsic `command` would submit.
+pub async fn record_calls<R: DeipRuntime>(
+    app: &R,
+    command: &CommandLine,
+) -> Result<Vec<(AccountId32, Vec<u8>)>> {
+    let endpoint = StepCommand::try_parse_from(command.args())?.endpoint;
+    ensure!(
+        matches!(endpoint, EndPoint::Tx { .. }),
+        "only tx commands can be encoded as calls"
+    );
+    // A nested command would take over the recording.
+    ensure!(
+        !app.call_recorder().is_recording(),
+        "a command run as a call can only nest hex encoded calls"
+    );
+    app.call_recorder().start();
+    let result = Box::pin(crate::run(app, endpoint)).await;
+    let calls = app.call_recorder().finish();
+    result?;
+    Ok(calls)
+}
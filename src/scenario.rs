@@ -0,0 +1,306 @@
+use anyhow::{bail, ensure, Context, Result};
+use clap::Parser;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app::Runtime,
+    cli::{EndPoint, Endpoints, RuntimeVersion, StepCommand, WaitFor},
+    config::{Interval, Pacing},
+    error::{self, ErrorKind},
+    output,
+    snapshot::fetch_value,
+};
+
+/// Steps run in order until the first failure.
+///
+/// ```toml
+/// [[steps]]
+/// name = "Create asset 0"
+/// run = "tx assets create -s alice -a alice -i 0 -m 1"
+/// expect_events = ["Assets::Created"]
+///
+/// [[steps]]
+/// interval = "finalized"
+/// interval_blocks = 2
+/// expect_storage = [{ pallet = "DeipFNFT", entry = "NextFTokenId", value = "0x05000000" }]
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub steps: Vec<Step>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub name: Option<String>,
+    /// Any command but `run`, without the global options.
+    pub run: Option<CommandLine>,
+    /// Pause after the command, the `--interval` options if not given.
+    pub interval: Option<Interval>,
+    pub interval_duration: Option<u64>,
+    pub interval_blocks: Option<u32>,
+    /// `Pallet::Variant` events the command must emit. Listing
+    /// `System::ExtrinsicFailed` lets the step pass on a failed dispatch.
+    #[serde(default)]
+    pub expect_events: Vec<String>,
+    #[serde(default)]
+    pub expect_storage: Vec<StorageExpectation>,
+}
+
+/// A command line split on whitespace, or its arguments one by one.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum CommandLine {
+    Line(String),
+    Args(Vec<String>),
+}
+
+/// Raw storage entry in the snapshot format: hex key after the entry prefix
+/// and hex SCALE encoded value. Without a `value` the entry must be absent.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageExpectation {
+    pub pallet: String,
+    pub entry: String,
+    #[serde(default)]
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StepReport {
+    /// 1-based position in the scenario.
+    step: usize,
+    title: String,
+    outcome: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    elapsed_ms: u128,
+}
+
+const EXTRINSIC_FAILED: &str = "System::ExtrinsicFailed";
+
+#[derive(Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl Scenario {
+    pub async fn load(path: &Path) -> Result<Self> {
+        read_document(path).await
+    }
+}
+
+impl CommandLine {
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            Self::Line(line) => line.split_whitespace().map(str::to_owned).collect(),
+            Self::Args(args) => args.clone(),
+        }
+    }
+}
+
+/// Reads a `.toml` or `.yaml` document.
+pub async fn read_document<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let document = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&text)?,
+        Some("yaml" | "yml") => serde_yaml::from_str(&text)?,
+        _ => bail!("{}: expected a .toml or .yaml file", path.display()),
+    };
+    Ok(document)
+}
+
+impl Step {
+    fn title(&self) -> String {
+        match (&self.name, &self.run) {
+            (Some(name), _) => name.clone(),
+            (None, Some(CommandLine::Line(line))) => line.clone(),
+            (None, Some(CommandLine::Args(args))) => args.join(" "),
+            (None, None) => "checks".to_owned(),
+        }
+    }
+
+    fn pacing(&self, default: Pacing) -> Pacing {
+        Pacing {
+            interval: self.interval.unwrap_or(default.interval),
+            duration: self
+                .interval_duration
+                .map_or(default.duration, Duration::from_secs),
+            blocks: self.interval_blocks.unwrap_or(default.blocks),
+        }
+    }
+}
+
+/// Runs every step against one connection, reconnecting to the same node only
+/// when a step changes the runtime spec version, then reports each step's
+/// outcome.
+pub async fn run(
+    path: &Path,
+    endpoints: &Endpoints,
+    force: Option<RuntimeVersion>,
+    wait: WaitFor,
+    pacing: Pacing,
+) -> Result<()> {
+    let scenario = Scenario::load(path).await?;
+    let (mut runtime, url) = Runtime::connect_with_url(endpoints, force, wait).await?;
+    let mut reports = Vec::with_capacity(scenario.steps.len());
+    let mut failed = false;
+
+    for (index, step) in scenario.steps.iter().enumerate() {
+        let mut report = StepReport {
+            step: index + 1,
+            title: step.title(),
+            outcome: Outcome::Skipped,
+            error: None,
+            elapsed_ms: 0,
+        };
+        if !failed {
+            info!("Step {}: {}", report.step, report.title);
+            let started = Instant::now();
+            match run_step(&runtime, step, pacing).await {
+                Ok(()) => report.outcome = Outcome::Passed,
+                Err(err) => {
+                    failed = true;
+                    report.outcome = Outcome::Failed;
+                    report.error = Some(format!("{err:#}"));
+                }
+            }
+            report.elapsed_ms = started.elapsed().as_millis();
+
+            if force.is_none() && !failed {
+                if let Err(err) = follow_upgrade(&mut runtime, &url, wait).await {
+                    failed = true;
+                    report.outcome = Outcome::Failed;
+                    report.error = Some(format!("{err:#}"));
+                }
+            }
+        }
+        reports.push(report);
+    }
+
+    output::emit(&reports, |reports| display_report(path, reports))?;
+    let passed = reports
+        .iter()
+        .filter(|report| report.outcome == Outcome::Passed)
+        .count();
+    ensure!(
+        !failed,
+        "Scenario failed, {passed}/{} steps passed",
+        reports.len()
+    );
+    info!("Scenario passed, {passed} steps");
+    Ok(())
+}
+
+/// Reconnects to the upgraded node at `url` when the last step changed the
+/// runtime spec version.
+async fn follow_upgrade(runtime: &mut Runtime, url: &str, wait: WaitFor) -> Result<()> {
+    let spec_version = runtime
+        .client()
+        .rpc()
+        .runtime_version(None)
+        .await
+        .context("failed to read the runtime version after the step")?
+        .spec_version;
+    if spec_version != runtime.spec_version() {
+        info!("Runtime upgraded to spec_version {spec_version}, reconnecting");
+        *runtime = Runtime::connect_url(url, wait).await?;
+    }
+    Ok(())
+}
+
+fn display_report(path: &Path, reports: &[StepReport]) {
+    info!("Scenario {}:", path.display());
+    for report in reports {
+        let StepReport {
+            step,
+            title,
+            elapsed_ms,
+            ..
+        } = report;
+        match (&report.outcome, &report.error) {
+            (Outcome::Failed, Some(error)) => {
+                error!(" [failed]  {step}. {title} ({elapsed_ms} ms): {error}")
+            }
+            (Outcome::Skipped, _) => info!(" [skipped] {step}. {title}"),
+            _ => info!(" [passed]  {step}. {title} ({elapsed_ms} ms)"),
+        }
+    }
+}
+
+async fn run_step(runtime: &Runtime, step: &Step, pacing: Pacing) -> Result<()> {
+    runtime.event_log().take();
+    if let Some(command) = &step.run {
+        let endpoint = StepCommand::try_parse_from(command.args())?.endpoint;
+        let result = match endpoint {
+            EndPoint::Run { .. } => bail!("Scenarios can't be nested"),
+            EndPoint::Events { .. } => bail!("Watching events never ends, it can't be a step"),
+            EndPoint::Diff { pre, post } => crate::diff(&pre, &post).await,
+            endpoint => match runtime {
+                Runtime::Master(app) => crate::run(app, endpoint).await,
+                Runtime::Develop(app) => crate::run(app, endpoint).await,
+            },
+        };
+        match result {
+            Err(err)
+                if error::kind(&err) == Some(ErrorKind::Dispatch)
+                    && step
+                        .expect_events
+                        .iter()
+                        .any(|event| event == EXTRINSIC_FAILED) =>
+            {
+                info!("Expected failure: {err:#}")
+            }
+            result => result?,
+        }
+    }
+
+    let events = runtime.event_log().take();
+    for expected in &step.expect_events {
+        ensure!(
+            events.contains(expected),
+            "expected event {expected}, emitted: {events:?}"
+        );
+    }
+
+    crate::wait_for(runtime.client(), step.pacing(pacing)).await?;
+
+    for expectation in &step.expect_storage {
+        check_storage(runtime, expectation).await?;
+    }
+    Ok(())
+}
+
+async fn check_storage(runtime: &Runtime, expectation: &StorageExpectation) -> Result<()> {
+    let StorageExpectation {
+        pallet,
+        entry,
+        key,
+        value,
+    } = expectation;
+    let raw_key = hex::decode(key.trim_start_matches("0x"))?;
+    let found = fetch_value(runtime.client(), pallet, entry, &raw_key)
+        .await?
+        .map(|found| format!("0x{}", hex::encode(found)));
+    let expected = value
+        .as_ref()
+        .map(|value| format!("0x{}", value.trim_start_matches("0x").to_lowercase()));
+    ensure!(
+        found == expected,
+        "{pallet}::{entry}({key}): expected {}, found {}",
+        expected.as_deref().unwrap_or("no value"),
+        found.as_deref().unwrap_or("no value"),
+    );
+    Ok(())
+}
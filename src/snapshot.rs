@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+use subxt::{
+    sp_core::{hashing::twox_128, storage::StorageKey, H256},
+    Client, DefaultConfig, Metadata,
+};
+
+use crate::{app::metadata_at, cli::RuntimeVersion};
+
+/// Keys requested per `state_getKeysPaged` call.
+const PAGE_SIZE: u32 = 512;
+
+/// Hex encoded key, without the pallet and entry prefixes, to hex encoded value.
+pub type StorageMap = BTreeMap<String, String>;
+
+/// Raw storage of a set of pallets at one block.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub runtime: String,
+    pub spec_version: u32,
+    pub block_hash: String,
+    /// Pallet name to storage entry name to its entries.
+    pub pallets: BTreeMap<String, BTreeMap<String, StorageMap>>,
+}
+
+impl Snapshot {
+    /// Reads `pallets` at block `at`, as laid out by the runtime of that block.
+    pub async fn take(
+        client: &Client<DefaultConfig>,
+        pallets: &[String],
+        at: H256,
+    ) -> Result<Self> {
+        let spec_version = client.rpc().runtime_version(Some(at)).await?.spec_version;
+        let runtime =
+            RuntimeVersion::for_spec_version(spec_version).map_or("unknown", RuntimeVersion::name);
+        let metadata = metadata_at(client, at).await?;
+        let mut snapshot = Self {
+            runtime: runtime.to_owned(),
+            spec_version,
+            block_hash: format!("{at:?}"),
+            pallets: BTreeMap::new(),
+        };
+        for (pallet, prefix, entries) in storage_entries(&metadata, pallets) {
+            let mut maps = BTreeMap::new();
+            for entry in entries {
+                let map = fetch_entry(client, &prefix, &entry, at).await?;
+                info!("{pallet}::{entry}: {} keys", map.len());
+                maps.insert(entry, map);
+            }
+            snapshot.pallets.insert(pallet, maps);
+        }
+        Ok(snapshot)
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        write_json(path, self).await
+    }
+
+    pub async fn load(path: &Path) -> Result<Self> {
+        read_json(path).await
+    }
+}
+
+pub async fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let json = serde_json::to_vec_pretty(value)?;
+    tokio::fs::write(path, json)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+pub async fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let json = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&json).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Differences of one storage map between two snapshots.
+#[derive(Serialize)]
+pub struct MapDiff {
+    pub pallet: String,
+    pub entry: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+/// Compares every storage map present in either snapshot.
+pub fn diff(pre: &Snapshot, post: &Snapshot) -> Vec<MapDiff> {
+    let empty = StorageMap::new();
+    let mut names: Vec<(&String, &String)> = pre
+        .pallets
+        .iter()
+        .chain(&post.pallets)
+        .flat_map(|(pallet, maps)| maps.keys().map(move |entry| (pallet, entry)))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|(pallet, entry)| {
+            let pre = storage_map(pre, pallet, entry).unwrap_or(&empty);
+            let post = storage_map(post, pallet, entry).unwrap_or(&empty);
+            MapDiff {
+                pallet: pallet.clone(),
+                entry: entry.clone(),
+                added: post
+                    .keys()
+                    .filter(|key| !pre.contains_key(*key))
+                    .cloned()
+                    .collect(),
+                removed: pre
+                    .keys()
+                    .filter(|key| !post.contains_key(*key))
+                    .cloned()
+                    .collect(),
+                changed: pre
+                    .iter()
+                    .filter(|(key, value)| post.get(*key).is_some_and(|post| post != *value))
+                    .map(|(key, _)| key.clone())
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn storage_map<'a>(snapshot: &'a Snapshot, pallet: &str, entry: &str) -> Option<&'a StorageMap> {
+    snapshot.pallets.get(pallet)?.get(entry)
+}
+
+/// `(pallet, storage prefix, entry names)` of the requested pallets found in metadata.
+fn storage_entries(metadata: &Metadata, pallets: &[String]) -> Vec<(String, String, Vec<String>)> {
+    pallets
+        .iter()
+        .filter_map(|name| {
+            let pallet = metadata
+                .runtime_metadata()
+                .pallets
+                .iter()
+                .find(|pallet| &pallet.name == name);
+            let storage = match pallet.and_then(|pallet| pallet.storage.as_ref()) {
+                Some(storage) => storage,
+                None => {
+                    warn!("{name}: no storage in the runtime metadata, skipped");
+                    return None;
+                }
+            };
+            let entries = storage.entries.iter().map(|entry| entry.name.clone());
+            Some((name.clone(), storage.prefix.clone(), entries.collect()))
+        })
+        .collect()
+}
+
+/// Raw value of one storage entry, `key` being its part after the entry prefix
+/// as found in snapshots.
+pub async fn fetch_value(
+    client: &Client<DefaultConfig>,
+    pallet: &str,
+    entry: &str,
+    key: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    let prefix = storage_entries(&client.metadata().read(), &[pallet.to_owned()])
+        .pop()
+        .map(|(_, prefix, _)| prefix)
+        .ok_or_else(|| anyhow!("{pallet}: no storage in the runtime metadata"))?;
+    let mut storage_key = entry_prefix(&prefix, entry);
+    storage_key.extend(key);
+    let value = client.rpc().storage(&StorageKey(storage_key), None).await?;
+    Ok(value.map(|value| value.0))
+}
+
+fn entry_prefix(prefix: &str, entry: &str) -> Vec<u8> {
+    let mut entry_prefix = twox_128(prefix.as_bytes()).to_vec();
+    entry_prefix.extend(twox_128(entry.as_bytes()));
+    entry_prefix
+}
+
+async fn fetch_entry(
+    client: &Client<DefaultConfig>,
+    prefix: &str,
+    entry: &str,
+    at: H256,
+) -> Result<StorageMap> {
+    let entry_prefix = entry_prefix(prefix, entry);
+    let prefix_len = entry_prefix.len();
+    let entry_prefix = StorageKey(entry_prefix);
+
+    let mut map = StorageMap::new();
+    let mut start_key = None;
+    loop {
+        let keys = client
+            .rpc()
+            .storage_keys_paged(Some(entry_prefix.clone()), PAGE_SIZE, start_key, Some(at))
+            .await?;
+        for key in &keys {
+            if let Some(value) = client.rpc().storage(key, Some(at)).await? {
+                map.insert(hex::encode(&key.0[prefix_len..]), hex::encode(value.0));
+            }
+        }
+        if keys.len() < PAGE_SIZE as usize {
+            return Ok(map);
+        }
+        start_key = keys.last().cloned();
+    }
+}
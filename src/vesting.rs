@@ -0,0 +1,262 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use subxt::sp_runtime::AccountId32;
+
+use crate::{
+    app::{DeipRuntime, VestingPlan},
+    output,
+};
+
+#[derive(Serialize)]
+struct VestingReport<'a> {
+    account: &'a AccountId32,
+    /// Best block timestamp the amounts are computed at.
+    now: u64,
+    plan: VestingPlan,
+    /// Vested so far, what stays unlocked after an `unlock` now.
+    vested: u128,
+    /// Vested but still under the DeipVesting lock, what an `unlock` now
+    /// releases.
+    unlockable: u128,
+    /// What stays locked after an `unlock` now.
+    locked: u128,
+    releases: Vec<Release>,
+}
+
+#[derive(Serialize)]
+struct Release {
+    time: u64,
+    amount: u128,
+    /// Total vested once released.
+    vested: u128,
+}
+
+impl VestingPlan {
+    /// Amount vested at `time`. The initial amount vests at the start, the rest
+    /// in equal parts every interval until the end of the plan. Before the end
+    /// of the cliff nothing more vests; the intervals run from the start with
+    /// `vesting_during_cliff`, otherwise from the end of the cliff.
+    pub fn vested_at(&self, time: u64) -> u128 {
+        if time < self.start_time {
+            return 0;
+        }
+        let elapsed = time - self.start_time;
+        if elapsed >= self.total_duration {
+            return self.total_amount;
+        }
+        if elapsed < self.cliff_duration {
+            return self.initial_amount;
+        }
+        let vesting_start = self.vesting_start();
+        let interval = self.interval.max(1);
+        let intervals = u128::from(((self.total_duration - vesting_start) / interval).max(1));
+        let passed = u128::from((elapsed - vesting_start) / interval).min(intervals);
+        let vesting = self.total_amount.saturating_sub(self.initial_amount);
+        // Split so that large balances don't overflow.
+        let vested = vesting / intervals * passed + vesting % intervals * passed / intervals;
+        self.initial_amount + vested
+    }
+
+    /// Releases after `time`, at most `limit` of them.
+    fn releases(&self, time: u64, limit: usize) -> Vec<Release> {
+        let mut releases = Vec::new();
+        let mut time = time;
+        let mut vested = self.vested_at(time);
+        while releases.len() < limit && vested < self.total_amount {
+            let next = match self.next_step(time) {
+                Some(next) => next,
+                None => break,
+            };
+            let next_vested = self.vested_at(next);
+            if next_vested > vested {
+                releases.push(Release {
+                    time: next,
+                    amount: next_vested - vested,
+                    vested: next_vested,
+                });
+                vested = next_vested;
+            }
+            time = next;
+        }
+        releases
+    }
+
+    /// First time after `time` the vested amount may change.
+    fn next_step(&self, time: u64) -> Option<u64> {
+        let end = self.start_time.saturating_add(self.total_duration);
+        let cliff_end = self.start_time.saturating_add(self.cliff_duration);
+        if time < self.start_time {
+            return Some(self.start_time);
+        }
+        if time >= end {
+            return None;
+        }
+        if time < cliff_end {
+            return Some(cliff_end.min(end));
+        }
+        let vesting_start = self.start_time + self.vesting_start();
+        let interval = self.interval.max(1);
+        let step = vesting_start + ((time - vesting_start) / interval + 1) * interval;
+        Some(step.min(end))
+    }
+
+    fn vesting_start(&self) -> u64 {
+        if self.vesting_during_cliff {
+            0
+        } else {
+            self.cliff_duration.min(self.total_duration)
+        }
+    }
+}
+
+/// Outputs the vesting plan of `account` with what is vested and unlockable at
+/// the best block and the next `limit` releases.
+pub async fn show<R: DeipRuntime>(app: &R, account: &AccountId32, limit: usize) -> Result<()> {
+    let plan = app
+        .vesting_plan(account)
+        .await?
+        .ok_or_else(|| anyhow!("No vesting plan for {account}"))?;
+    let now = app.timestamp().await?;
+    let vested = plan.vested_at(now);
+    // Earlier unlocks lowered the lock to what was still vesting then.
+    let released = plan
+        .total_amount
+        .saturating_sub(app.vesting_lock(account).await?);
+    let report = VestingReport {
+        account,
+        now,
+        vested,
+        unlockable: vested.saturating_sub(released),
+        locked: plan.total_amount.saturating_sub(vested),
+        releases: plan.releases(now, limit),
+        plan,
+    };
+    output::emit(&report, display_report)
+}
+
+fn display_report(report: &VestingReport) {
+    let VestingReport {
+        account,
+        now,
+        plan,
+        vested,
+        unlockable,
+        locked,
+        releases,
+    } = report;
+    info!(
+        "Vesting of {account} at {now}: {vested} of {} vested, {unlockable} unlockable, \
+         {locked} locked",
+        plan.total_amount
+    );
+    info!(
+        " Plan: start {}, cliff {} ms, duration {} ms, interval {} ms, initial {}{}",
+        plan.start_time,
+        plan.cliff_duration,
+        plan.total_duration,
+        plan.interval,
+        plan.initial_amount,
+        if plan.vesting_during_cliff {
+            ", vesting during the cliff"
+        } else {
+            ""
+        },
+    );
+    for Release {
+        time,
+        amount,
+        vested,
+    } in releases
+    {
+        info!(
+            " - {time} (in {}): {amount}, {vested} vested",
+            format_delay(time - now)
+        );
+    }
+}
+
+fn format_delay(ms: u64) -> String {
+    let secs = ms / 1000;
+    match (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, minutes) => format!("{minutes}m {}s", secs % 60),
+        (0, hours, minutes) => format!("{hours}h {minutes}m"),
+        (days, hours, _) => format!("{days}d {hours}h"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 100 vests at 1000, then 100 every 100 ms from the end of the cliff at
+    /// 1100 until 2000.
+    fn plan(vesting_during_cliff: bool) -> VestingPlan {
+        VestingPlan {
+            start_time: 1000,
+            cliff_duration: 100,
+            total_duration: 1000,
+            interval: 100,
+            initial_amount: 100,
+            total_amount: 1000,
+            vesting_during_cliff,
+        }
+    }
+
+    #[test]
+    fn only_initial_amount_vests_during_cliff() {
+        let plan = plan(false);
+        assert_eq!(plan.vested_at(999), 0);
+        assert_eq!(plan.vested_at(1000), 100);
+        assert_eq!(plan.vested_at(1099), 100);
+        assert_eq!(plan.vested_at(1100), 100);
+    }
+
+    #[test]
+    fn cliff_end_releases_what_vested_during_it() {
+        let plan = plan(true);
+        assert_eq!(plan.vested_at(1099), 100);
+        assert_eq!(plan.vested_at(1100), 190);
+    }
+
+    #[test]
+    fn vests_at_interval_boundaries() {
+        let plan = plan(false);
+        assert_eq!(plan.vested_at(1199), 100);
+        assert_eq!(plan.vested_at(1200), 200);
+        assert_eq!(plan.vested_at(1299), 200);
+        assert_eq!(plan.vested_at(1300), 300);
+    }
+
+    #[test]
+    fn whole_amount_vests_at_end() {
+        let plan = plan(false);
+        assert_eq!(plan.vested_at(1999), 900);
+        assert_eq!(plan.vested_at(2000), 1000);
+        assert_eq!(plan.vested_at(u64::MAX), 1000);
+    }
+
+    #[test]
+    fn next_step_walks_start_cliff_intervals_and_end() {
+        let plan = plan(false);
+        assert_eq!(plan.next_step(0), Some(1000));
+        assert_eq!(plan.next_step(1000), Some(1100));
+        assert_eq!(plan.next_step(1100), Some(1200));
+        assert_eq!(plan.next_step(1150), Some(1200));
+        assert_eq!(plan.next_step(1950), Some(2000));
+        assert_eq!(plan.next_step(2000), None);
+    }
+
+    #[test]
+    fn releases_list_each_interval_until_end() {
+        let plan = plan(false);
+        let releases = plan.releases(1000, usize::MAX);
+        assert_eq!(releases.len(), 9);
+        let first = &releases[0];
+        assert_eq!((first.time, first.amount, first.vested), (1200, 100, 200));
+        let last = &releases[8];
+        assert_eq!((last.time, last.amount, last.vested), (2000, 100, 1000));
+        assert_eq!(plan.releases(1000, 2).len(), 2);
+        assert!(plan.releases(2000, usize::MAX).is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::{ensure, Context, Result};
+use sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT;
+use std::path::Path;
+use subxt::sp_core::{hashing::blake2_256, H256};
+
+/// `\0asm` magic followed by the wasm binary format version 1.
+const WASM_HEADER: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+/// Runtime code ready to be passed to `System::set_code`.
+pub struct RuntimeWasm {
+    /// Blob as read from disk, this is what gets submitted.
+    pub code: Vec<u8>,
+    pub uncompressed_size: usize,
+    /// Blake2-256 of `code`, the same hash the node reports for `:code`.
+    pub hash: H256,
+}
+
+impl RuntimeWasm {
+    pub async fn load(path: &Path) -> Result<Self> {
+        let code = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read runtime wasm {}", path.display()))?;
+        let uncompressed = sp_maybe_compressed_blob::decompress(&code, CODE_BLOB_BOMB_LIMIT)
+            .with_context(|| format!("failed to decompress {}", path.display()))?;
+        ensure!(
+            uncompressed.starts_with(&WASM_HEADER),
+            "{} is not a wasm blob",
+            path.display()
+        );
+        let uncompressed_size = uncompressed.len();
+        let hash = H256(blake2_256(&code));
+        Ok(Self {
+            code,
+            uncompressed_size,
+            hash,
+        })
+    }
+}